@@ -1,7 +1,8 @@
 import React, { useState, useRef, useEffect } from 'react';
 import './TreeTypePanel.css';
 
-export type TreeType = 'fir' | 'birch' | 'ivy';
+// A species preset name as listed by the engine's preset_names()
+export type TreeType = string;
 
 interface TreeTypeOption {
   id: TreeType;
@@ -11,11 +12,13 @@ interface TreeTypeOption {
 }
 
 interface TreeTypePanelProps {
+  treeTypes: TreeType[];
   selectedTreeType: TreeType;
   onTreeTypeChange: (treeType: TreeType) => void;
 }
 
-const treeTypes: TreeTypeOption[] = [
+// Display details for the presets we know; any other preset gets a generic entry
+const knownTreeTypes: TreeTypeOption[] = [
   {
     id: 'fir',
     name: 'Fir',
@@ -36,7 +39,16 @@ const treeTypes: TreeTypeOption[] = [
   }
 ];
 
+const describeTreeType = (id: TreeType): TreeTypeOption =>
+  knownTreeTypes.find(type => type.id === id) ?? {
+    id,
+    name: id.charAt(0).toUpperCase() + id.slice(1),
+    description: 'Species preset',
+    icon: '🌳'
+  };
+
 export const TreeTypePanel: React.FC<TreeTypePanelProps> = ({
+  treeTypes: treeTypeIds,
   selectedTreeType,
   onTreeTypeChange
 }) => {
@@ -57,7 +69,8 @@ export const TreeTypePanel: React.FC<TreeTypePanelProps> = ({
     };
   }, []);
 
-  const selectedOption = treeTypes.find(type => type.id === selectedTreeType);
+  const treeTypes = treeTypeIds.map(describeTreeType);
+  const selectedOption = describeTreeType(selectedTreeType);
 
   const handleOptionClick = (treeType: TreeType) => {
     onTreeTypeChange(treeType);
@@ -70,8 +83,8 @@ export const TreeTypePanel: React.FC<TreeTypePanelProps> = ({
         className="tree-type-trigger"
         onClick={() => setIsOpen(!isOpen)}
       >
-        <span className="tree-type-trigger-icon">{selectedOption?.icon}</span>
-        <span className="tree-type-trigger-text">{selectedOption?.name}</span>
+        <span className="tree-type-trigger-icon">{selectedOption.icon}</span>
+        <span className="tree-type-trigger-text">{selectedOption.name}</span>
         <span className="tree-type-trigger-arrow">{isOpen ? '▲' : '▼'}</span>
       </button>
       
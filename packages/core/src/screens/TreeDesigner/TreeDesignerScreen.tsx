@@ -11,6 +11,7 @@ export const TreeDesignerScreen: React.FC = () => {
   const initializedRef = useRef<boolean>(false);
   const [downloadGltf, setDownloadGltf] = useState<(() => void) | null>(null);
   const [selectedTreeType, setSelectedTreeType] = useState<TreeType>('fir');
+  const [treeTypes, setTreeTypes] = useState<TreeType[]>([]);
   const [currentCleanup, setCurrentCleanup] = useState<(() => void) | null>(null);
   const [regenerateScene, setRegenerateScene] = useState<(() => void) | null>(null);
 
@@ -34,11 +35,13 @@ export const TreeDesignerScreen: React.FC = () => {
         setCurrentCleanup(() => result.cleanup);
         setDownloadGltf(() => result.downloadGltf);
         setRegenerateScene(() => result.regenerateScene);
+        setTreeTypes(result.presetNames);
       }
     } else if (initPromise && typeof initPromise === 'object') {
       setCurrentCleanup(() => initPromise.cleanup);
       setDownloadGltf(() => initPromise.downloadGltf);
       setRegenerateScene(() => initPromise.regenerateScene);
+      setTreeTypes(initPromise.presetNames);
     }
   };
 
@@ -70,7 +73,6 @@ export const TreeDesignerScreen: React.FC = () => {
   const handleTreeTypeChange = (treeType: TreeType) => {
     console.log(`Tree type changed to: ${treeType}`);
     setSelectedTreeType(treeType);
-    // TODO: Apply tree type presets to the tree generation
   };
 
   const menuSections: MenuSection[] = [
@@ -94,6 +96,7 @@ export const TreeDesignerScreen: React.FC = () => {
     <div className="tree-designer-screen">
       <TopMenu sections={menuSections} />
       <TreeTypePanel
+        treeTypes={treeTypes}
         selectedTreeType={selectedTreeType}
         onTreeTypeChange={handleTreeTypeChange}
       />
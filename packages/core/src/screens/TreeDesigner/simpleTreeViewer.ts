@@ -1,17 +1,18 @@
 import * as THREE from 'three';
 import { OrbitControls } from 'three/examples/jsm/controls/OrbitControls.js';
 import { GLTFLoader } from 'three/examples/jsm/loaders/GLTFLoader.js';
+import type { TreeType } from '../../components/TreeTypePanel/TreeTypePanel';
 
 let animationId: number | null = null;
 
-export const initializeSimpleTreeViewer = async (container: HTMLElement, initialTreeType: 'fir' | 'birch' | 'ivy' = 'fir'): Promise<{ cleanup: () => void; downloadGltf: () => void; regenerateScene: (newTreeType?: 'fir' | 'birch' | 'ivy') => void } | void> => {
+export const initializeSimpleTreeViewer = async (container: HTMLElement, initialTreeType: TreeType = 'fir'): Promise<{ cleanup: () => void; downloadGltf: () => void; regenerateScene: (newTreeType?: TreeType) => void; presetNames: TreeType[] } | void> => {
   let currentTreeType = initialTreeType;
   // Import WASM module
-  const { default: init, generate } = await eval(`import("/static/js/tree_rs.js")`);
+  const { default: init, TreeObject, preset_names } = await eval(`import("/static/js/tree_rs.js")`);
   
   await init();
-  const tree = generate(123, 5.0, 1.0);
-  console.log("Rust generate:", tree);
+  // The tree types offered are the engine's species presets
+  const presetNames: TreeType[] = preset_names();
 
   // Scene setup with simple gradient background
   const scene = new THREE.Scene();
@@ -124,7 +125,7 @@ export const initializeSimpleTreeViewer = async (container: HTMLElement, initial
   };
 
   // Generate and display tree or ivy scene based on type
-  const generateScene = (newTreeType?: 'fir' | 'birch' | 'ivy') => {
+  const generateScene = (newTreeType?: TreeType) => {
     if (newTreeType) {
       currentTreeType = newTreeType;
     }
@@ -204,27 +205,6 @@ export const initializeSimpleTreeViewer = async (container: HTMLElement, initial
         });
       }
     } else {
-      // Generate regular tree for fir and birch (for now, same tree)
-      const newTree = generate(123, 5.0, 1.0);
-      
-      if (newTree && newTree.rings) {
-        newTree.rings.forEach((ring: any, index: number) => {
-          const radius = ring.radius || 0.1;
-          const height = 0.2;
-          
-          const geometry = new THREE.CylinderGeometry(radius, radius * 0.8, height, 8);
-          const material = new THREE.MeshLambertMaterial({ 
-            color: new THREE.Color().setHSL(0.1, 0.7, 0.3 + Math.random() * 0.2)
-          });
-          
-          const segment = new THREE.Mesh(geometry, material);
-          segment.position.set(ring.center?.x || 0, ring.center?.y || index * 0.2, ring.center?.z || 0);
-          segment.castShadow = true;
-          
-          treeGroup.add(segment);
-        });
-      }
-      
       // Add green debug cube only for Fir and Birch scenes
       const debugCube = new THREE.Mesh(
         new THREE.BoxGeometry(2, 2, 2),
@@ -235,6 +215,25 @@ export const initializeSimpleTreeViewer = async (container: HTMLElement, initial
       scene.add(debugCube);
     }
     
+    // Every tree type is grown from the engine preset of the same name
+    const presetTree = TreeObject.from_preset(currentTreeType, 123);
+    const segmentGeometry = new THREE.CylinderGeometry(1, 0.8, 0.2, 8);
+    const segmentMaterial = new THREE.MeshLambertMaterial({
+      color: new THREE.Color().setHSL(0.1, 0.7, 0.4)
+    });
+    for (let index = 0; index < presetTree.rings_count(); index++) {
+      const center = presetTree.ring_center(index);
+      const radius = presetTree.ring_radius(index) ?? 0.1;
+
+      const segment = new THREE.Mesh(segmentGeometry, segmentMaterial);
+      segment.position.set(center.x, center.y, center.z);
+      segment.scale.set(radius, 1, radius);
+      segment.castShadow = true;
+
+      treeGroup.add(segment);
+    }
+    presetTree.free();
+
     scene.add(treeGroup);
   };
 
@@ -315,5 +314,5 @@ export const initializeSimpleTreeViewer = async (container: HTMLElement, initial
     });
   };
   
-  return { cleanup, downloadGltf, regenerateScene: generateScene };
+  return { cleanup, downloadGltf, regenerateScene: generateScene, presetNames };
 };
\ No newline at end of file
@@ -13,7 +13,7 @@ export const initializeTreeViewer = async (container: HTMLElement): Promise<(()
     return;
   }
   // Import WASM module
-  const { default: init, generate } = await eval(`import("/static/js/tree_rs.js")`);
+  const { default: init, generate, parameter_schema } = await eval(`import("/static/js/tree_rs.js")`);
   
   await init();
   const tree = generate(123, 5.0, 1.0);
@@ -783,109 +783,177 @@ export const initializeTreeViewer = async (container: HTMLElement): Promise<(()
     });
   }
 
-  treeFolder.add(treeParams, 'height', 0.1, 10).onChange((value: number) => {
-    tree.set_trunk_height(value);
-    treeParams.height = value;
-    redrawTree();
+  // Engine parameter behind each slider, by its dotted schema name
+  const engineFields = {
+    height: 'trunk.height',
+    butressing: 'trunk.buttressing',
+    splitHeight: 'trunk.split_height',
+    segmentLength: 'trunk.segment_length',
+    trunkSize: 'trunk.size',
+    trunkRingSpread: 'trunk.ring_spread',
+    segmentLengthVariation: 'trunk.segment_length_variation',
+    branchAngleMin: 'branching.angle_min',
+    branchAngleMax: 'branching.angle_max',
+    bendAngleMin: 'branching.bend_angle_min',
+    bendAngleMax: 'branching.bend_angle_max',
+    branchFrequencyMin: 'branching.frequency_min',
+    branchFrequencyMax: 'branching.frequency_max',
+    radiusTaper: 'branching.radius_taper',
+    branchAzimuthVariation: 'branching.azimuth_variation',
+    maxBranchReach: 'branching.max_reach',
+    maxDepth: 'general.max_depth',
+    rootEnable: 'roots.enable',
+    rootDepth: 'roots.depth',
+    rootSpread: 'roots.spread',
+    rootDensity: 'roots.density',
+    rootSegmentLength: 'roots.segment_length',
+    twigEnable: 'twigs.enable',
+    twigDensity: 'twigs.density',
+    twigScale: 'twigs.scale',
+    twigAngleVariation: 'twigs.angle_variation',
+  } as const;
+
+  // Slider ranges as the engine validates them, so the GUI never drifts from it
+  type ParamSpec = { name: string; min: number; max: number; step: number };
+  const paramSpecs = new Map<string, ParamSpec>(
+    JSON.parse(parameter_schema()).map((spec: ParamSpec) => [spec.name, spec])
+  );
+
+  function addParamSlider(folder: dat.GUI, key: keyof typeof engineFields) {
+    const spec = paramSpecs.get(engineFields[key])!;
+    return folder.add(treeParams, key, spec.min, spec.max, spec.step);
+  }
+
+  // Mirror the engine's parameters into the GUI, e.g. after it rejected a value
+  function syncParamsFromTree() {
+    const params = JSON.parse(tree.params_json());
+    for (const [key, field] of Object.entries(engineFields)) {
+      const [group, name] = field.split('.');
+      Object.assign(treeParams, { [key]: params[group][name] });
+    }
+    updateGuiDisplay(gui);
+  }
+
+  function updateGuiDisplay(folder: dat.GUI) {
+    folder.__controllers.forEach(controller => controller.updateDisplay());
+    Object.values(folder.__folders).forEach(updateGuiDisplay);
+  }
+
+  // Why the engine rejected the last change, shown until a change is accepted
+  const parameterNotice = document.createElement('div');
+  container.appendChild(parameterNotice);
+  parameterNotice.style.position = 'absolute';
+  parameterNotice.style.top = '80px';
+  parameterNotice.style.left = '10px';
+  parameterNotice.style.maxWidth = '360px';
+  parameterNotice.style.padding = '8px 12px';
+  parameterNotice.style.borderRadius = '4px';
+  parameterNotice.style.background = 'rgba(150, 40, 40, 0.9)';
+  parameterNotice.style.color = '#fff';
+  parameterNotice.style.font = '12px sans-serif';
+  parameterNotice.style.whiteSpace = 'pre-line';
+  parameterNotice.style.zIndex = '1000';
+  parameterNotice.style.display = 'none';
+
+  // The engine setters throw a structured error for values it rejects, such as
+  // a split height above the trunk. Its messages are shown to the user and the
+  // sliders go back to the engine's values
+  function updateTree(update: () => void) {
+    try {
+      update();
+      redrawTree();
+      parameterNotice.style.display = 'none';
+    } catch (error) {
+      console.warn('Tree parameter rejected:', error);
+      const { message, violations } = error as { message?: string; violations?: { message: string }[] };
+      parameterNotice.textContent = violations?.length
+        ? violations.map(violation => violation.message).join('\n')
+        : message ?? String(error);
+      parameterNotice.style.display = 'block';
+    }
+    syncParamsFromTree();
+  }
+
+  addParamSlider(treeFolder, 'height').onChange((value: number) => {
+    updateTree(() => {
+      // Lower the split height along with the trunk instead of having the engine reject it
+      tree.begin_update();
+      try {
+        if (treeParams.splitHeight > value) {
+          tree.set_split_height(value);
+        }
+        tree.set_trunk_height(value);
+      } finally {
+        tree.commit_update();
+      }
+    });
   });
 
-  treeFolder.add(treeParams, 'butressing', 0.1, 5).onChange((value: number) => {
-    tree.set_butressing(value);
-    treeParams.butressing = value;
-    redrawTree();
+  addParamSlider(treeFolder, 'butressing').onChange((value: number) => {
+    updateTree(() => tree.set_butressing(value));
   });
 
-  treeFolder.add(treeParams, 'splitHeight', 0.5, 8).onChange((value: number) => {
-    tree.set_split_height(value);
-    treeParams.splitHeight = value;
-    redrawTree();
+  addParamSlider(treeFolder, 'splitHeight').onChange((value: number) => {
+    updateTree(() => tree.set_split_height(value));
   });
 
-  treeFolder.add(treeParams, 'trunkSize', 0.1, 10.0).name('Trunk Size').onChange((value: number) => {
-    tree.set_trunk_size(value);
-    treeParams.trunkSize = value;
-    redrawTree();
+  addParamSlider(treeFolder, 'trunkSize').name('Trunk Size').onChange((value: number) => {
+    updateTree(() => tree.set_trunk_size(value));
   });
 
-  treeFolder.add(treeParams, 'branchAzimuthVariation', 0.0, 1.0).name('3D Branch Spread').onChange((value: number) => {
-    tree.set_branch_azimuth_variation(value);
-    treeParams.branchAzimuthVariation = value;
-    redrawTree();
+  addParamSlider(treeFolder, 'branchAzimuthVariation').name('3D Branch Spread').onChange((value: number) => {
+    updateTree(() => tree.set_branch_azimuth_variation(value));
   });
 
-  treeFolder.add(treeParams, 'maxBranchReach', 2.0, 50.0).name('Max Branch Reach').onChange((value: number) => {
-    tree.set_max_branch_reach(value);
-    treeParams.maxBranchReach = value;
-    redrawTree();
+  addParamSlider(treeFolder, 'maxBranchReach').name('Max Branch Reach').onChange((value: number) => {
+    updateTree(() => tree.set_max_branch_reach(value));
   });
 
   // Advanced Parameters
   const advancedFolder = gui.addFolder('Advanced Parameters');
 
-  advancedFolder.add(treeParams, 'segmentLength', 0.1, 1.0).name('Segment Length').onChange((value: number) => {
-    tree.set_segment_length(value);
-    treeParams.segmentLength = value;
-    redrawTree();
+  addParamSlider(advancedFolder, 'segmentLength').name('Segment Length').onChange((value: number) => {
+    updateTree(() => tree.set_segment_length(value));
   });
 
-  advancedFolder.add(treeParams, 'branchAngleMin', 5.0, 45.0).name('Min Branch Angle (°)').onChange((value: number) => {
-    tree.set_branch_angle_range(value, treeParams.branchAngleMax);
-    treeParams.branchAngleMin = value;
-    redrawTree();
+  addParamSlider(advancedFolder, 'branchAngleMin').name('Min Branch Angle (°)').onChange((value: number) => {
+    updateTree(() => tree.set_branch_angle_range(value, treeParams.branchAngleMax));
   });
 
-  advancedFolder.add(treeParams, 'branchAngleMax', 15.0, 90.0).name('Max Branch Angle (°)').onChange((value: number) => {
-    tree.set_branch_angle_range(treeParams.branchAngleMin, value);
-    treeParams.branchAngleMax = value;
-    redrawTree();
+  addParamSlider(advancedFolder, 'branchAngleMax').name('Max Branch Angle (°)').onChange((value: number) => {
+    updateTree(() => tree.set_branch_angle_range(treeParams.branchAngleMin, value));
   });
 
-  advancedFolder.add(treeParams, 'bendAngleMin', -45.0, 0.0).name('Min Bend Angle (°)').onChange((value: number) => {
-    tree.set_bend_angle_range(value, treeParams.bendAngleMax);
-    treeParams.bendAngleMin = value;
-    redrawTree();
+  addParamSlider(advancedFolder, 'bendAngleMin').name('Min Bend Angle (°)').onChange((value: number) => {
+    updateTree(() => tree.set_bend_angle_range(value, treeParams.bendAngleMax));
   });
 
-  advancedFolder.add(treeParams, 'bendAngleMax', 0.0, 45.0).name('Max Bend Angle (°)').onChange((value: number) => {
-    tree.set_bend_angle_range(treeParams.bendAngleMin, value);
-    treeParams.bendAngleMax = value;
-    redrawTree();
+  addParamSlider(advancedFolder, 'bendAngleMax').name('Max Bend Angle (°)').onChange((value: number) => {
+    updateTree(() => tree.set_bend_angle_range(treeParams.bendAngleMin, value));
   });
 
-  advancedFolder.add(treeParams, 'branchFrequencyMin', 1, 10).name('Min Branch Frequency').onChange((value: number) => {
-    tree.set_branch_frequency_range(Math.floor(value), treeParams.branchFrequencyMax);
-    treeParams.branchFrequencyMin = Math.floor(value);
-    redrawTree();
+  addParamSlider(advancedFolder, 'branchFrequencyMin').name('Min Branch Frequency').onChange((value: number) => {
+    updateTree(() => tree.set_branch_frequency_range(Math.floor(value), treeParams.branchFrequencyMax));
   });
 
-  advancedFolder.add(treeParams, 'branchFrequencyMax', 2, 15).name('Max Branch Frequency').onChange((value: number) => {
-    tree.set_branch_frequency_range(treeParams.branchFrequencyMin, Math.floor(value));
-    treeParams.branchFrequencyMax = Math.floor(value);
-    redrawTree();
+  addParamSlider(advancedFolder, 'branchFrequencyMax').name('Max Branch Frequency').onChange((value: number) => {
+    updateTree(() => tree.set_branch_frequency_range(treeParams.branchFrequencyMin, Math.floor(value)));
   });
 
-  advancedFolder.add(treeParams, 'maxDepth', 1, 20).name('Max Depth').onChange((value: number) => {
-    tree.set_max_depth(Math.floor(value));
-    treeParams.maxDepth = Math.floor(value);
-    redrawTree();
+  addParamSlider(advancedFolder, 'maxDepth').name('Max Depth').onChange((value: number) => {
+    updateTree(() => tree.set_max_depth(Math.floor(value)));
   });
 
-  advancedFolder.add(treeParams, 'radiusTaper', 0.1, 0.8).name('Radius Taper').onChange((value: number) => {
-    tree.set_radius_taper(value);
-    treeParams.radiusTaper = value;
-    redrawTree();
+  addParamSlider(advancedFolder, 'radiusTaper').name('Radius Taper').onChange((value: number) => {
+    updateTree(() => tree.set_radius_taper(value));
   });
 
-  advancedFolder.add(treeParams, 'trunkRingSpread', 0.0, 5.0).name('Ring Spread').onChange((value: number) => {
-    tree.set_trunk_ring_spread(value);
-    treeParams.trunkRingSpread = value;
-    redrawTree();
+  addParamSlider(advancedFolder, 'trunkRingSpread').name('Ring Spread').onChange((value: number) => {
+    updateTree(() => tree.set_trunk_ring_spread(value));
   });
 
-  advancedFolder.add(treeParams, 'segmentLengthVariation', 0.0, 1.0).name('Segment Variation').onChange((value: number) => {
-    tree.set_segment_length_variation(value);
-    treeParams.segmentLengthVariation = value;
-    redrawTree();
+  addParamSlider(advancedFolder, 'segmentLengthVariation').name('Segment Variation').onChange((value: number) => {
+    updateTree(() => tree.set_segment_length_variation(value));
   });
 
   // Visualization controls
@@ -920,60 +988,42 @@ export const initializeTreeViewer = async (container: HTMLElement): Promise<(()
   const rootFolder = gui.addFolder('Root System');
 
   rootFolder.add(treeParams, 'rootEnable').name('Enable Roots').onChange((value: boolean) => {
-    tree.set_root_enable(value);
-    treeParams.rootEnable = value;
-    redrawTree();
+    updateTree(() => tree.set_root_enable(value));
   });
 
-  rootFolder.add(treeParams, 'rootDepth', 0.5, 3.0).name('Root Depth').onChange((value: number) => {
-    tree.set_root_depth(value);
-    treeParams.rootDepth = value;
-    redrawTree();
+  addParamSlider(rootFolder, 'rootDepth').name('Root Depth').onChange((value: number) => {
+    updateTree(() => tree.set_root_depth(value));
   });
 
-  rootFolder.add(treeParams, 'rootSpread', 0.5, 2.0).name('Root Spread').onChange((value: number) => {
-    tree.set_root_spread(value);
-    treeParams.rootSpread = value;
-    redrawTree();
+  addParamSlider(rootFolder, 'rootSpread').name('Root Spread').onChange((value: number) => {
+    updateTree(() => tree.set_root_spread(value));
   });
 
-  rootFolder.add(treeParams, 'rootDensity', 2, 8).step(1).name('Root Count').onChange((value: number) => {
-    tree.set_root_density(Math.floor(value));
-    treeParams.rootDensity = Math.floor(value);
-    redrawTree();
+  addParamSlider(rootFolder, 'rootDensity').name('Root Count').onChange((value: number) => {
+    updateTree(() => tree.set_root_density(Math.floor(value)));
   });
 
-  rootFolder.add(treeParams, 'rootSegmentLength', 0.1, 0.8).name('Root Segment Length').onChange((value: number) => {
-    tree.set_root_segment_length(value);
-    treeParams.rootSegmentLength = value;
-    redrawTree();
+  addParamSlider(rootFolder, 'rootSegmentLength').name('Root Segment Length').onChange((value: number) => {
+    updateTree(() => tree.set_root_segment_length(value));
   });
 
   // Twig system controls
   const twigFolder = gui.addFolder('Twig System');
 
   twigFolder.add(treeParams, 'twigEnable').name('Enable Twigs').onChange((value: boolean) => {
-    tree.set_twig_enable(value);
-    treeParams.twigEnable = value;
-    redrawTree();
+    updateTree(() => tree.set_twig_enable(value));
   });
 
-  twigFolder.add(treeParams, 'twigDensity', 0.1, 2.0).name('Twig Density').onChange((value: number) => {
-    tree.set_twig_density(value);
-    treeParams.twigDensity = value;
-    redrawTree();
+  addParamSlider(twigFolder, 'twigDensity').name('Twig Density').onChange((value: number) => {
+    updateTree(() => tree.set_twig_density(value));
   });
 
-  twigFolder.add(treeParams, 'twigScale', 0.1, 3.0).name('Twig Size').onChange((value: number) => {
-    tree.set_twig_scale(value);
-    treeParams.twigScale = value;
-    redrawTree();
+  addParamSlider(twigFolder, 'twigScale').name('Twig Size').onChange((value: number) => {
+    updateTree(() => tree.set_twig_scale(value));
   });
 
-  twigFolder.add(treeParams, 'twigAngleVariation', 0.0, 1.0).name('Angle Variation').onChange((value: number) => {
-    tree.set_twig_angle_variation(value);
-    treeParams.twigAngleVariation = value;
-    redrawTree();
+  addParamSlider(twigFolder, 'twigAngleVariation').name('Angle Variation').onChange((value: number) => {
+    updateTree(() => tree.set_twig_angle_variation(value));
   });
 
   twigFolder.add(treeParams, 'twigBaseAngle', -90, 90).name('Base Angle (deg)').onChange((value: number) => {
@@ -1099,6 +1149,17 @@ export const initializeTreeViewer = async (container: HTMLElement): Promise<(()
     }
   };
 
+  // Start the sliders on the engine's defaults rather than the literals above
+  syncParamsFromTree();
+
+  // The engine merges repeated changes to a parameter into one undo step until
+  // the gesture ends, so close the step whenever a control is released
+  function endGestureOnRelease(folder: dat.GUI) {
+    folder.__controllers.forEach(controller => controller.onFinishChange(() => tree.end_gesture()));
+    Object.values(folder.__folders).forEach(endGestureOnRelease);
+  }
+  endGestureOnRelease(gui);
+
   // Collapse all folders by default - preparing for new high-level controls
   // treeFolder.open();
   // advancedFolder.open();
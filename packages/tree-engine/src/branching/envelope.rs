@@ -0,0 +1,201 @@
+use crate::core::{EnvelopeMode, EnvelopeParams, EnvelopeShape};
+use crate::modifiers::bend::rotate_toward;
+use glam::Vec3;
+
+/// Largest turn in radians applied to one escaping segment at full steer strength
+const MAX_STEER_PER_SEGMENT: f32 = std::f32::consts::FRAC_PI_2;
+
+/// Fraction of the height where the flame shape is widest
+const FLAME_PEAK: f32 = 0.7;
+
+/// Points this close to the trunk axis, as a fraction of the widest radius,
+/// are inside anywhere below the top, so the leader can climb through narrow
+/// parts of the profile such as the bottom of an ellipsoid
+const AXIS_ALLOWANCE: f32 = 0.1;
+
+/// Crown volume built from `EnvelopeParams`. Below the crown base the envelope
+/// does not apply, so the trunk and low limbs are governed by the split
+/// height as before.
+#[derive(Debug, Clone)]
+pub struct CrownEnvelope {
+    shape: EnvelopeShape,
+    pub mode: EnvelopeMode,
+    base: f32,
+    height: f32,
+    radius: f32,
+    steer_strength: f32,
+    profile: Vec<f32>,
+}
+
+impl CrownEnvelope {
+    /// The envelope described by `params`, or `None` when no shape is selected.
+    pub fn from_params(params: &EnvelopeParams) -> Option<Self> {
+        if params.shape == EnvelopeShape::None {
+            return None;
+        }
+        Some(CrownEnvelope {
+            shape: params.shape,
+            mode: params.mode,
+            base: params.base,
+            height: params.height.max(0.01),
+            radius: params.radius,
+            steer_strength: params.steer_strength,
+            profile: params.profile.clone(),
+        })
+    }
+
+    /// Lowest and highest points of the crown.
+    pub fn vertical_range(&self) -> (f32, f32) {
+        (self.base, self.base + self.height)
+    }
+
+    /// Widest radius of the crown.
+    pub fn max_radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Radius of the crown at height `y`, or `None` above the crown top.
+    /// Heights below the base report unlimited radius.
+    pub fn radius_at(&self, y: f32) -> Option<f32> {
+        let t = (y - self.base) / self.height;
+        if t < 0.0 {
+            return Some(f32::INFINITY);
+        }
+        if t > 1.0 {
+            return None;
+        }
+
+        let fraction = match self.shape {
+            EnvelopeShape::None | EnvelopeShape::Cylinder => 1.0,
+            EnvelopeShape::Cone => 1.0 - t,
+            EnvelopeShape::InvertedCone => t,
+            EnvelopeShape::Ellipsoid => (1.0 - (2.0 * t - 1.0).powi(2)).max(0.0).sqrt(),
+            EnvelopeShape::Flame => {
+                if t <= FLAME_PEAK {
+                    t / FLAME_PEAK
+                } else {
+                    (1.0 - t) / (1.0 - FLAME_PEAK)
+                }
+            }
+            EnvelopeShape::Custom => sample_profile(&self.profile, t),
+        };
+        Some(self.radius * fraction)
+    }
+
+    /// Whether `point` lies inside the crown, measured from the trunk axis.
+    pub fn contains(&self, point: Vec3) -> bool {
+        let horizontal = Vec3::new(point.x, 0.0, point.z).length();
+        self.radius_at(point.y)
+            .is_some_and(|radius| horizontal <= radius.max(self.radius * AXIS_ALLOWANCE))
+    }
+
+    /// Turn a segment of length `step` growing from `position` along
+    /// `direction` back toward the inside of the crown: down when it would
+    /// pass the top, toward the trunk axis otherwise. When the turn the steer
+    /// strength allows still leaves the crown, the segment heads level toward
+    /// the axis instead. Returns `None` when neither keeps the step inside,
+    /// and the limb should stop there.
+    pub fn steer(&self, position: Vec3, direction: Vec3, step: f32) -> Option<Vec3> {
+        let next = position + direction * step;
+        let inward = Vec3::new(-position.x, 0.0, -position.z).normalize_or_zero();
+        let target = if self.radius_at(next.y).is_none() || inward == Vec3::ZERO {
+            Vec3::NEG_Y
+        } else {
+            inward
+        };
+        let angle = self.steer_strength * MAX_STEER_PER_SEGMENT;
+        let steered = (rotate_toward(direction, target, angle) * direction).normalize();
+        [steered, inward]
+            .into_iter()
+            .find(|&candidate| candidate != Vec3::ZERO && self.contains(position + candidate * step))
+    }
+}
+
+/// Piecewise-linear interpolation of evenly spaced profile samples.
+fn sample_profile(profile: &[f32], t: f32) -> f32 {
+    match profile {
+        [] => 1.0,
+        [only] => *only,
+        _ => {
+            let position = t * (profile.len() - 1) as f32;
+            let index = (position.floor() as usize).min(profile.len() - 2);
+            let blend = position - index as f32;
+            profile[index] + (profile[index + 1] - profile[index]) * blend
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{TreeParameters, generator::ModularTreeGenerator};
+
+    const SHAPES: [EnvelopeShape; 6] = [
+        EnvelopeShape::Cone,
+        EnvelopeShape::Ellipsoid,
+        EnvelopeShape::Cylinder,
+        EnvelopeShape::InvertedCone,
+        EnvelopeShape::Flame,
+        EnvelopeShape::Custom,
+    ];
+
+    fn envelope(shape: EnvelopeShape, mode: EnvelopeMode) -> CrownEnvelope {
+        let params = EnvelopeParams { shape, mode, steer_strength: 1.0, ..EnvelopeParams::default() };
+        CrownEnvelope::from_params(&params).unwrap()
+    }
+
+    #[test]
+    fn steered_steps_stay_inside_every_shape() {
+        let step = 0.3;
+        for shape in SHAPES {
+            let envelope = envelope(shape, EnvelopeMode::Steer);
+            let (base, top) = envelope.vertical_range();
+            // Limbs leaving the trunk axis outward and upward at several heights
+            for start in 1..10 {
+                let mut position = Vec3::new(0.0, base + (top - base) * start as f32 / 10.0, 0.0);
+                let mut direction = Vec3::new(1.0, 0.6, 0.3).normalize();
+                assert!(envelope.contains(position), "{:?} start {}", shape, start);
+                for _ in 0..100 {
+                    if !envelope.contains(position + direction * step) {
+                        direction = envelope.steer(position, direction, step).unwrap();
+                    }
+                    position += direction * step;
+                    assert!(envelope.contains(position), "{:?} start {} left at {}", shape, start, position);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_trunk_axis_is_inside_up_to_the_top() {
+        for shape in SHAPES {
+            let envelope = envelope(shape, EnvelopeMode::Prune);
+            let (base, top) = envelope.vertical_range();
+            let near_axis = envelope.max_radius() * AXIS_ALLOWANCE * 0.9;
+            for step in 0..=20 {
+                let y = base + (top - base) * step as f32 / 20.0;
+                assert!(envelope.contains(Vec3::new(near_axis, y, 0.0)), "{:?} at {}", shape, y);
+            }
+            assert!(!envelope.contains(Vec3::new(0.0, top + 0.1, 0.0)), "{:?}", shape);
+        }
+    }
+
+    #[test]
+    fn grown_trees_stay_inside_every_shape() {
+        for mode in [EnvelopeMode::Prune, EnvelopeMode::Steer] {
+            for shape in SHAPES {
+                let params = TreeParameters {
+                    envelope: EnvelopeParams { shape, mode, height: 6.0, radius: 2.0, ..EnvelopeParams::default() },
+                    ..TreeParameters::default()
+                };
+                let envelope = CrownEnvelope::from_params(&params.envelope).unwrap();
+                let tree = ModularTreeGenerator::new().generate_tree(&params);
+
+                assert!(tree.cross_sections.len() > 10, "{:?} {:?}", mode, shape);
+                for cross_section in &tree.cross_sections {
+                    assert!(envelope.contains(cross_section.center), "{:?} {:?} grew to {}", mode, shape, cross_section.center);
+                }
+            }
+        }
+    }
+}
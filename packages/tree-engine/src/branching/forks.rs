@@ -0,0 +1,257 @@
+use crate::core::BranchingParams;
+use crate::structure::{ComponentRing, RingType};
+use crate::trunk::RingGenerator;
+use glam::{Vec2, Vec3, Quat};
+use rand::{Rng, rngs::SmallRng};
+use std::f32::consts::TAU;
+
+/// Radius factor of the axis that carries on through a fork
+pub const CONTINUATION_SHARE: f32 = 0.95;
+/// Radius factor of a lone side branch; pairs and whorls divide it between them
+pub const SIDE_BRANCH_SHARE: f32 = 0.8;
+
+/// One child limb leaving a fork.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ForkChild {
+    pub direction: Vec3,
+    pub radius_share: f32,    // Radius relative to a single-ring parent, before taper
+    pub continues_axis: bool, // Whether the child carries on the parent's axis
+}
+
+/// Draw a branch angle from the configured range, in radians.
+pub fn branch_angle(params: &BranchingParams, rng: &mut SmallRng) -> f32 {
+    let angle_min = params.angle_min.min(params.angle_max);
+    let angle_max = params.angle_max.max(params.angle_min);
+    rng.gen_range(angle_min..=angle_max).to_radians()
+}
+
+/// Tilt `main_direction` away by `angle`, then spin it `azimuth` radians
+/// around the original axis.
+pub fn spread_direction(main_direction: Vec3, angle: f32, azimuth: f32) -> Vec3 {
+    // Traditional 2D branching (base method)
+    let up_component = Vec3::Y;
+    let perpendicular = if main_direction.cross(up_component).length() > 0.1 {
+        main_direction.cross(up_component).normalize()
+    } else {
+        Vec3::X
+    };
+    let planar_rotation = Quat::from_axis_angle(perpendicular, angle);
+    let planar_direction = (planar_rotation * main_direction).normalize();
+
+    (Quat::from_axis_angle(main_direction, azimuth) * planar_direction).normalize()
+}
+
+/// A lateral node: the parent axis continues and one side branch leaves at
+/// each azimuth, tilted by the matching entry of `angles`.
+pub fn lateral_children(main_direction: Vec3, angles: &[f32], azimuths: &[f32]) -> Vec<ForkChild> {
+    // Branches sharing a node split the area of a lone side branch
+    let share = if azimuths.len() > 1 {
+        SIDE_BRANCH_SHARE / (azimuths.len() as f32).sqrt()
+    } else {
+        SIDE_BRANCH_SHARE
+    };
+
+    let continuation = ForkChild {
+        direction: main_direction,
+        radius_share: CONTINUATION_SHARE,
+        continues_axis: true,
+    };
+    let side_branches = angles.iter().zip(azimuths).map(|(&angle, &azimuth)| ForkChild {
+        direction: spread_direction(main_direction, angle, azimuth),
+        radius_share: share,
+        continues_axis: false,
+    });
+    std::iter::once(continuation).chain(side_branches).collect()
+}
+
+/// A co-dominant fork: the parent axis ends and `codominant_count` equal
+/// stems spread evenly around it, each leaning out by about `codominant_angle`.
+pub fn codominant_children(main_direction: Vec3, params: &BranchingParams, rng: &mut SmallRng) -> Vec<ForkChild> {
+    let count = params.codominant_count.max(2);
+    let share = CONTINUATION_SHARE / (count as f32).sqrt();
+    let start_azimuth = rng.gen_range(0.0..TAU);
+
+    (0..count)
+        .map(|i| {
+            let angle = (params.codominant_angle * rng.gen_range(0.8..=1.2)).to_radians();
+            let azimuth = start_azimuth + TAU * i as f32 / count as f32;
+            ForkChild {
+                direction: spread_direction(main_direction, angle, azimuth),
+                radius_share: share,
+                continues_axis: false,
+            }
+        })
+        .collect()
+}
+
+/// Replace the fixed radius shares of `children` with pipe model shares.
+///
+/// The parent's `r^pipe_exponent` is divided between the children in
+/// proportion to their importance: the continuing axis counts 1, a side
+/// branch `lateral_importance`, and co-dominant stems count equally.
+pub fn apply_pipe_model(children: &mut [ForkChild], params: &BranchingParams) {
+    let importance = |child: &ForkChild| if child.continues_axis { 1.0 } else { params.lateral_importance.max(0.0) };
+    let total: f32 = children.iter().map(importance).sum();
+    if total <= 0.0 {
+        return;
+    }
+    let exponent = params.pipe_exponent.max(1.0);
+    for child in children.iter_mut() {
+        child.radius_share = (importance(child) / total).powf(1.0 / exponent);
+    }
+}
+
+/// Rings for every child of a pipe model fork, in the order of `children`.
+///
+/// The continuing axis (or the first stem of a co-dominant fork) keeps all of
+/// the parent's rings scaled by its share, so a buttressed trunk stays
+/// buttressed; every other child gets a single ring sized from the parent's
+/// effective radius.
+pub fn pipe_rings(parent_rings: &[ComponentRing], children: &[ForkChild]) -> Vec<Vec<ComponentRing>> {
+    let ring_generator = RingGenerator::new();
+    let parent_radius = ring_generator.effective_radius(parent_rings);
+
+    children
+        .iter()
+        .enumerate()
+        .map(|(index, child)| {
+            if index == 0 {
+                parent_rings
+                    .iter()
+                    .map(|ring| ring_generator.create_child_ring_from_parent(ring, child.radius_share))
+                    .collect()
+            } else {
+                vec![ComponentRing {
+                    offset: Vec2::ZERO,
+                    radius: parent_radius * child.radius_share,
+                    ring_type: RingType::SideBranch,
+                }]
+            }
+        })
+        .collect()
+}
+
+/// Rings for every child of a fork, in the order of `children`.
+///
+/// A single-ring parent gives each child a copy scaled by its radius share.
+/// A multi-ring (buttressed) parent is split instead: every child after the
+/// first takes one ring from the end of the list while the first child keeps
+/// the rest, so the buttress separates into limbs. Children left over once
+/// only one ring remains get a scaled copy of the last ring.
+pub fn split_rings(parent_rings: &[ComponentRing], children: &[ForkChild], segment_taper: f32) -> Vec<Vec<ComponentRing>> {
+    let ring_generator = RingGenerator::new();
+    let Some(last_ring) = parent_rings.last() else {
+        return vec![Vec::new(); children.len()];
+    };
+
+    if parent_rings.len() == 1 {
+        return children
+            .iter()
+            .map(|child| vec![ring_generator.create_child_ring_from_parent(last_ring, segment_taper * child.radius_share)])
+            .collect();
+    }
+
+    let mut split = vec![Vec::new(); children.len()];
+    let mut kept = parent_rings.len();
+    for (child_rings, child) in split.iter_mut().zip(children).skip(1) {
+        let ring = if kept > 1 {
+            kept -= 1;
+            ring_generator.create_child_ring_from_parent(&parent_rings[kept], segment_taper)
+        } else {
+            ring_generator.create_child_ring_from_parent(last_ring, segment_taper * child.radius_share)
+        };
+        child_rings.push(ring);
+    }
+    if let Some(first) = split.first_mut() {
+        *first = parent_rings[..kept]
+            .iter()
+            .map(|ring| ring_generator.create_child_ring_from_parent(ring, segment_taper * CONTINUATION_SHARE))
+            .collect();
+    }
+    split
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{RadiusModel, TreeParameters, generator::ModularTreeGenerator};
+    use crate::structure::TreeStructure;
+    use rand::SeedableRng;
+
+    fn ring(offset: Vec2, radius: f32) -> ComponentRing {
+        ComponentRing { offset, radius, ring_type: RingType::MainTrunk }
+    }
+
+    fn pipe_params(exponent: f32) -> BranchingParams {
+        BranchingParams { radius_model: RadiusModel::PipeModel, pipe_exponent: exponent, ..BranchingParams::default() }
+    }
+
+    fn assert_conserved(parent_rings: &[ComponentRing], child_rings: &[Vec<ComponentRing>], exponent: f32) {
+        let ring_generator = RingGenerator::new();
+        let parent = ring_generator.effective_radius(parent_rings).powf(exponent);
+        let children: f32 = child_rings.iter().map(|rings| ring_generator.effective_radius(rings).powf(exponent)).sum();
+        assert!((parent - children).abs() <= parent * 1e-4, "parent {} children {}", parent, children);
+    }
+
+    #[test]
+    fn pipe_rings_conserve_the_parent_area() {
+        let single = vec![ring(Vec2::ZERO, 0.3)];
+        let buttressed = vec![ring(Vec2::new(0.1, 0.0), 0.2), ring(Vec2::new(-0.1, 0.0), 0.2), ring(Vec2::new(0.0, 0.1), 0.15)];
+        let mut rng = SmallRng::seed_from_u64(3);
+
+        for exponent in [2.0, 2.5, 3.0] {
+            let params = BranchingParams { codominant_count: 3, ..pipe_params(exponent) };
+            let forks = [
+                lateral_children(Vec3::Y, &[0.6], &[0.0]),
+                lateral_children(Vec3::Y, &[0.6, 0.7, 0.5], &[0.0, 2.0, 4.0]),
+                codominant_children(Vec3::Y, &params, &mut rng),
+            ];
+            for mut children in forks {
+                apply_pipe_model(&mut children, &params);
+                for parent_rings in [&single, &buttressed] {
+                    assert_conserved(parent_rings, &pipe_rings(parent_rings, &children), exponent);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pipe_model_forks_conserve_the_parent_area_in_a_grown_tree() {
+        let params = TreeParameters {
+            branching: BranchingParams { codominant_probability: 0.2, ..pipe_params(2.5) },
+            ..TreeParameters::default()
+        };
+        let tree = ModularTreeGenerator::new().generate_tree(&params);
+
+        let is_root = |index: usize| {
+            tree.cross_sections[index].component_rings.iter().any(|ring| matches!(ring.ring_type, RingType::Root { .. }))
+        };
+        let mut forks = 0;
+        for (index, cross_section) in tree.cross_sections.iter().enumerate() {
+            let children = &cross_section.children_indices;
+            if children.len() < 2 || is_root(index) || children.iter().any(|&child| is_root(child)) {
+                continue;
+            }
+            let child_rings: Vec<Vec<ComponentRing>> = children.iter().map(|&child| tree.cross_sections[child].component_rings.clone()).collect();
+            assert_conserved(&cross_section.component_rings, &child_rings, 2.5);
+            forks += 1;
+        }
+        assert!(forks > 10);
+    }
+
+    #[test]
+    fn pipe_settings_are_ignored_with_fixed_radii() {
+        let radii = |tree: &TreeStructure| -> Vec<f32> {
+            tree.cross_sections.iter().flat_map(|cs| cs.component_rings.iter().map(|ring| ring.radius)).collect()
+        };
+        let default = ModularTreeGenerator::new().generate_tree(&TreeParameters::default());
+
+        let mut params = TreeParameters::default();
+        params.branching.pipe_exponent = 3.0;
+        params.branching.lateral_importance = 0.9;
+        let tree = ModularTreeGenerator::new().generate_tree(&params);
+
+        assert_eq!(params.branching.radius_model, RadiusModel::Fixed);
+        assert_eq!(radii(&tree), radii(&default));
+    }
+}
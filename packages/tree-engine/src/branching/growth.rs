@@ -0,0 +1,330 @@
+use crate::branching::tropism::tropism_rotation;
+use crate::branching::{forks, BranchingPatterns, BranchingSystem, CrownEnvelope, ShadowGrid};
+use crate::core::{BranchGenerator, BranchingParams, EnvelopeMode, GenerationContext, TreeSubsystem};
+use crate::structure::{TreeStructure, BranchCrossSection, ComponentRing, RingType};
+use crate::trunk::RingGenerator;
+use glam::{Vec2, Vec3, Quat};
+use rand::{Rng, rngs::SmallRng};
+
+/// Radius of a segment in the year it grows; every later year adds a ring.
+const SHOOT_RADIUS: f32 = 0.01;
+
+/// Buds whose shoot would be shorter than this fraction of a segment never
+/// grow out, which keeps weak outer buds from multiplying forever.
+const MIN_SHOOT_FRACTION: f32 = 0.25;
+
+/// Most a well-lit bud's shoot can outgrow the tree's average one
+const MAX_LIGHT_VIGOR: f32 = 1.5;
+
+/// Annual growth: the tree is grown one year at a time from a single bud at
+/// the trunk base.
+///
+/// Each spring every terminal bud extends its axis by one shoot, and lateral
+/// buds formed the year before break with the `bud_break` chance or die.
+/// Shoots are split into pieces of the trunk segment length and bent by the
+/// bend range, tropisms and the crown envelope like the random walk model;
+/// lateral buds are placed along them by the phyllotaxis pattern and leave at
+/// the branch angle. Shoot length falls with branch order and with age.
+///
+/// With light competition on, a shadow grid is updated as shoots grow. Each
+/// spring the buds' light is measured first, so buds sharing a season see the
+/// same crown: shoot length scales with a bud's light relative to the average
+/// bud, buds below the shade tolerance die, and shoots stop at occluders.
+///
+/// Radius comes from secondary thickening: a segment starts at
+/// `SHOOT_RADIUS` and gains `ring_width` for every year it has existed. Every
+/// random draw happens in the order the simulation runs, so growing the same
+/// seed for fewer years gives exactly the younger version of the same tree.
+pub struct GrowthSystem {
+    patterns: BranchingPatterns,
+}
+
+/// A bud waiting for spring.
+#[derive(Debug, Clone)]
+struct Bud {
+    node: usize,        // Cross-section the bud sits on
+    direction: Vec3,    // Heading of the shoot it grows
+    order: u32,         // Branch order: 0 on the trunk, +1 for every lateral
+    vigor: f32,         // Shoot length relative to the leader's
+    lateral: bool,      // Lateral buds may fail to break; terminal buds always grow
+    nodes: u32,         // Bud nodes already formed along this axis, for phyllotaxis
+}
+
+/// Everything outside the tree that limits where shoots may grow.
+struct Surroundings {
+    envelope: Option<CrownEnvelope>,
+    shadows: Option<ShadowGrid>,
+}
+
+impl GrowthSystem {
+    pub fn new() -> Self {
+        GrowthSystem {
+            patterns: BranchingPatterns::new(),
+        }
+    }
+
+    /// Grow one annual shoot from `bud`, recording the year every new
+    /// cross-section formed in, and return the buds it sets for next year.
+    #[allow(clippy::too_many_arguments)]
+    fn grow_shoot(
+        &self,
+        bud: &Bud,
+        length: f32,
+        year: u32,
+        context: &GenerationContext,
+        surroundings: &Surroundings,
+        tree: &mut TreeStructure,
+        formed_in: &mut Vec<u32>,
+        rng: &mut SmallRng,
+    ) -> Vec<Bud> {
+        let branching = &context.params.branching;
+        let general = &context.params.general;
+        let step = context.params.trunk.segment_length.max(0.01);
+
+        let pieces = (length / step).ceil().max(1.0) as usize;
+        let piece_length = length / pieces as f32;
+        let bend_scale = branching.curves.bend_at(bud.order, general);
+        let bend_min = branching.bend_angle_min.min(branching.bend_angle_max) * bend_scale;
+        let bend_max = branching.bend_angle_max.max(branching.bend_angle_min) * bend_scale;
+
+        // Cross-section and heading of every piece of the shoot, base first
+        let mut shoot = Vec::with_capacity(pieces);
+        let mut node = bud.node;
+        let mut direction = bud.direction;
+        let mut pruned = false;
+        for _ in 0..pieces {
+            let bend_axis = Vec3::new(rng.gen_range(-1.0..=1.0), 0.0, rng.gen_range(-1.0..=1.0)).normalize_or(Vec3::X);
+            let bend = Quat::from_axis_angle(bend_axis, rng.gen_range(bend_min..=bend_max).to_radians());
+            direction = (bend * direction).normalize();
+            direction = (tropism_rotation(direction, bud.order, general, &context.params.tropism) * direction).normalize();
+
+            let center = tree.cross_sections[node].center;
+            if let Some(shadows) = &surroundings.shadows
+                && shadows.blocked(center + direction * piece_length)
+            {
+                pruned = true;
+                break;
+            }
+            if let Some(envelope) = &surroundings.envelope
+                && !envelope.contains(center + direction * piece_length)
+            {
+                match envelope.mode {
+                    EnvelopeMode::Prune => {
+                        pruned = true;
+                        break;
+                    }
+                    EnvelopeMode::Steer => match envelope.steer(center, direction, piece_length) {
+                        Some(steered) => direction = steered,
+                        None => {
+                            pruned = true;
+                            break;
+                        }
+                    },
+                }
+            }
+
+            let index = tree.cross_sections.len();
+            tree.cross_sections[node].children_indices.push(index);
+            tree.cross_sections.push(BranchCrossSection {
+                center: center + direction * piece_length,
+                orientation: Quat::from_rotation_arc(Vec3::Y, direction),
+                depth: bud.order,
+                component_rings: Vec::new(), // Sized once the simulation ends
+                children_indices: Vec::new(),
+                parent_index: Some(node),
+                branch_id: 0,
+            });
+            formed_in.push(year);
+            shoot.push((index, direction));
+            node = index;
+        }
+
+        let Some(&(tip, tip_direction)) = shoot.last() else {
+            return Vec::new();
+        };
+
+        let mut buds = Vec::new();
+        let bud_nodes = context.params.growth.buds_per_shoot;
+        if !pruned {
+            buds.push(Bud {
+                node: tip,
+                direction: tip_direction,
+                lateral: false,
+                nodes: bud.nodes + bud_nodes,
+                ..bud.clone()
+            });
+        }
+
+        // Lateral bud nodes from the tip down, spaced evenly along the shoot
+        let angle_scale = branching.curves.angle_at(bud.order + 1, general);
+        let spread = branching.curves.azimuth_spread_at(bud.order + 1, general);
+        for k in 0..bud_nodes as usize {
+            let (index, heading) = shoot[shoot.len() - 1 - k * shoot.len() / bud_nodes as usize];
+            let azimuths = self.patterns.side_branch_azimuths(branching, bud.nodes + k as u32, spread, rng);
+            for azimuth in azimuths {
+                let angle = forks::branch_angle(branching, rng) * angle_scale;
+                buds.push(Bud {
+                    node: index,
+                    direction: forks::spread_direction(heading, angle, azimuth),
+                    order: bud.order + 1,
+                    vigor: bud.vigor * context.params.growth.lateral_vigor,
+                    lateral: true,
+                    nodes: 0,
+                });
+            }
+        }
+        buds
+    }
+
+    /// Size every cross-section from its age: one growth ring per year since
+    /// it formed. The trunk keeps the buttressed base rings, scaled to fit.
+    fn thicken(tree: &mut TreeStructure, formed_in: &[u32], years: u32, ring_width: f32) {
+        let ring_generator = RingGenerator::new();
+        let base_rings = tree.cross_sections[0].component_rings.clone();
+        let base_radius = ring_generator.effective_radius(&base_rings);
+
+        for (cross_section, &year) in tree.cross_sections.iter_mut().zip(formed_in) {
+            let radius = SHOOT_RADIUS + ring_width * years.saturating_sub(year) as f32;
+            cross_section.component_rings = if cross_section.depth == 0 && base_radius > 0.0 {
+                ring_generator.scale_rings(&base_rings, radius / base_radius)
+            } else {
+                vec![ComponentRing {
+                    offset: Vec2::ZERO,
+                    radius,
+                    ring_type: RingType::SideBranch,
+                }]
+            };
+        }
+    }
+}
+
+impl TreeSubsystem for GrowthSystem {
+    type Params = BranchingParams;
+    type Output = ();
+
+    fn generate(
+        &self,
+        _params: &Self::Params,
+        context: &GenerationContext,
+        tree: &mut TreeStructure,
+        rng: &mut SmallRng,
+    ) -> Self::Output {
+        if tree.cross_sections.is_empty() {
+            return;
+        }
+
+        let growth = &context.params.growth;
+        let min_shoot = context.params.trunk.segment_length.max(0.01) * MIN_SHOOT_FRACTION;
+        let max_depth = context.params.general.max_depth;
+        let budget = context.params.general.max_cross_sections as usize;
+        let mut surroundings = Surroundings {
+            envelope: CrownEnvelope::from_params(&context.params.envelope),
+            shadows: ShadowGrid::from_params(&context.params.light),
+        };
+        let response = context.params.light.response.max(0.0);
+        let shade_tolerance = context.params.light.shade_tolerance;
+
+        // The trunk stage's cross-sections predate the first year
+        let mut formed_in = vec![0; tree.cross_sections.len()];
+        if let Some(shadows) = &mut surroundings.shadows {
+            for cross_section in &tree.cross_sections {
+                shadows.cast(cross_section.center);
+            }
+        }
+        let mut buds = vec![Bud {
+            node: 0,
+            direction: Vec3::Y,
+            order: 0,
+            vigor: 1.0,
+            lateral: false,
+            nodes: 0,
+        }];
+
+        let mut years = 0;
+        'seasons: for year in 1..=growth.age {
+            let season_vigor = 1.0 / (1.0 + growth.vigor_decline * (year - 1) as f32);
+            let exposures: Vec<f32> = match &surroundings.shadows {
+                Some(shadows) => buds.iter().map(|bud| shadows.exposure(tree.cross_sections[bud.node].center)).collect(),
+                None => vec![1.0; buds.len()],
+            };
+            let mean_exposure = exposures.iter().sum::<f32>() / exposures.len().max(1) as f32;
+
+            let mut next_buds = Vec::new();
+            for (bud, &exposure) in buds.iter().zip(&exposures) {
+                if bud.lateral && !rng.gen_bool(growth.bud_break.clamp(0.0, 1.0) as f64) {
+                    continue;
+                }
+                if surroundings.shadows.is_some() && exposure < shade_tolerance {
+                    continue;
+                }
+                let light_vigor = if mean_exposure > 0.0 {
+                    (exposure / mean_exposure).powf(response).min(MAX_LIGHT_VIGOR)
+                } else {
+                    1.0
+                };
+                let length = growth.shoot_length * bud.vigor * season_vigor * light_vigor * rng.gen_range(0.8..=1.2);
+                if length < min_shoot || bud.order >= max_depth {
+                    continue;
+                }
+                if tree.cross_sections.len() >= budget {
+                    tree.budget_exhausted = true;
+                    break 'seasons;
+                }
+                let first_new = tree.cross_sections.len();
+                next_buds.extend(self.grow_shoot(bud, length, year, context, &surroundings, tree, &mut formed_in, rng));
+                if let Some(shadows) = &mut surroundings.shadows {
+                    for cross_section in &tree.cross_sections[first_new..] {
+                        shadows.cast(cross_section.center);
+                    }
+                }
+            }
+            buds = next_buds;
+            years = year;
+        }
+
+        Self::thicken(tree, &formed_in, years, growth.ring_width);
+    }
+}
+
+impl BranchGenerator for GrowthSystem {
+    /// Edits are applied to the finished tree, after its growing seasons,
+    /// so a grafted limb is a random walk limb.
+    fn graft(&self, tree: &mut TreeStructure, parent: usize, direction: Vec3, context: &GenerationContext, rng: &mut SmallRng) {
+        BranchingSystem::new().graft(tree, parent, direction, context, rng);
+    }
+}
+
+impl Default for GrowthSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{BranchingModel, TreeParameters, generator::ModularTreeGenerator};
+    use crate::structure::TreeStructure;
+
+    fn grow(age: u32, light: bool) -> TreeStructure {
+        let mut params = TreeParameters::default();
+        params.branching.model = BranchingModel::Growth;
+        params.growth.age = age;
+        params.light.enabled = light;
+        params.roots.enable = false;
+        ModularTreeGenerator::new().generate_tree(&params)
+    }
+
+    #[test]
+    fn an_older_tree_grows_on_from_the_younger_one() {
+        for light in [false, true] {
+            let young = grow(6, light);
+            let old = grow(9, light);
+
+            assert!(old.cross_sections.len() > young.cross_sections.len());
+            for (index, (a, b)) in young.cross_sections.iter().zip(&old.cross_sections).enumerate() {
+                assert_eq!(a.center, b.center, "cross-section {} moved", index);
+                assert_eq!(a.parent_index, b.parent_index, "cross-section {} changed parent", index);
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::core::GuideCurve;
+use glam::Vec3;
+
+/// A guide curve measured by arc length, so an axis can walk along it in
+/// steps of the segment length.
+#[derive(Debug, Clone)]
+pub struct GuidePath {
+    points: Vec<Vec3>,
+    distances: Vec<f32>, // Arc length from the start to each point
+}
+
+/// How far an axis has come along one of the walk's guide paths.
+#[derive(Debug, Clone, Copy)]
+pub struct GuideProgress {
+    pub path: usize,
+    pub distance: f32,
+}
+
+impl GuidePath {
+    /// The path along `curve`, or `None` when it has fewer than two points
+    /// or no length.
+    pub fn from_curve(curve: &GuideCurve) -> Option<Self> {
+        let points = curve.polyline();
+        let mut distances = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        for (index, point) in points.iter().enumerate() {
+            if index > 0 {
+                total += point.distance(points[index - 1]);
+            }
+            distances.push(total);
+        }
+        (points.len() >= 2 && total > 0.0).then_some(GuidePath { points, distances })
+    }
+
+    pub fn length(&self) -> f32 {
+        self.distances.last().copied().unwrap_or(0.0)
+    }
+
+    pub fn start(&self) -> Vec3 {
+        self.points[0]
+    }
+
+    /// Point `distance` along the path, clamped to its ends.
+    pub fn point_at(&self, distance: f32) -> Vec3 {
+        let distance = distance.clamp(0.0, self.length());
+        let next = self.distances.partition_point(|&d| d < distance).clamp(1, self.points.len() - 1);
+        let (d0, d1) = (self.distances[next - 1], self.distances[next]);
+        let blend = if d1 > d0 { (distance - d0) / (d1 - d0) } else { 1.0 };
+        self.points[next - 1].lerp(self.points[next], blend)
+    }
+
+    /// Advance `step` from `distance` starting at `from`: the new distance,
+    /// the point there and the heading toward it. `None` once the path ends.
+    pub fn advance(&self, distance: f32, from: Vec3, step: f32) -> Option<(f32, Vec3, Vec3)> {
+        if distance >= self.length() {
+            return None;
+        }
+        let distance = (distance + step).min(self.length());
+        let point = self.point_at(distance);
+        let direction = (point - from).normalize_or(Vec3::Y);
+        Some((distance, point, direction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::GuideInterpolation;
+
+    // An L-shaped path: 2 up, then 1 along x
+    fn path() -> GuidePath {
+        let points = vec![Vec3::ZERO, Vec3::new(0.0, 2.0, 0.0), Vec3::new(1.0, 2.0, 0.0)];
+        GuidePath::from_curve(&GuideCurve::new(points, GuideInterpolation::Polyline)).unwrap()
+    }
+
+    #[test]
+    fn points_are_found_by_arc_length() {
+        let path = path();
+        assert_eq!(path.length(), 3.0);
+        assert_eq!(path.point_at(1.0), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(path.point_at(2.5), Vec3::new(0.5, 2.0, 0.0));
+        assert_eq!(path.point_at(-1.0), Vec3::ZERO);
+        assert_eq!(path.point_at(10.0), Vec3::new(1.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn advance_steps_to_the_end_and_stops() {
+        let path = path();
+        let (distance, point, direction) = path.advance(1.5, Vec3::new(0.0, 1.5, 0.0), 1.0).unwrap();
+        assert_eq!(distance, 2.5);
+        assert_eq!(point, Vec3::new(0.5, 2.0, 0.0));
+        assert_eq!(direction, (point - Vec3::new(0.0, 1.5, 0.0)).normalize());
+
+        // The last step is cut short at the end of the path
+        let (distance, point, _) = path.advance(2.5, point, 1.0).unwrap();
+        assert_eq!(distance, 3.0);
+        assert_eq!(point, Vec3::new(1.0, 2.0, 0.0));
+        assert!(path.advance(distance, point, 1.0).is_none());
+    }
+
+    #[test]
+    fn degenerate_curves_give_no_path() {
+        let single = GuideCurve::new(vec![Vec3::Y], GuideInterpolation::Polyline);
+        let still = GuideCurve::new(vec![Vec3::Y, Vec3::Y], GuideInterpolation::Polyline);
+        assert!(GuidePath::from_curve(&single).is_none());
+        assert!(GuidePath::from_curve(&still).is_none());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::core::{LightParams, Occluder};
+use glam::{IVec3, Vec3};
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+/// Fraction of a cell's shadow passed on to each cell one layer further down
+const SHADOW_FALLOFF: f32 = 0.5;
+
+/// Elevation rings and directions per ring used to see how much of the sky
+/// the occluders hide; the zenith is sampled as well
+const SKY_RINGS: u32 = 3;
+const SKY_RING_DIRECTIONS: u32 = 8;
+
+/// Coarse voxel grid of accumulated shadow, after Palubicki et al.'s shadow
+/// propagation. A cell only exists once something shades it, so the grid
+/// grows with the tree instead of needing bounds up front.
+///
+/// Occluders do not cast into the grid, since a tall box would darken
+/// everything near it completely; they hide the part of the sky they cover
+/// instead, so a bud beside a wall keeps about half its light.
+#[derive(Debug, Clone)]
+pub struct ShadowGrid {
+    voxel_size: f32,
+    strength: f32,
+    depth: i32,
+    occluders: Vec<Occluder>,
+    sky: Vec<Vec3>, // Directions sampled for occluder visibility
+    shadow: HashMap<IVec3, f32>,
+}
+
+impl ShadowGrid {
+    /// The empty grid described by `params`, or `None` when light
+    /// competition is off.
+    pub fn from_params(params: &LightParams) -> Option<Self> {
+        if !params.enabled {
+            return None;
+        }
+
+        let mut sky = vec![Vec3::Y];
+        for ring in 0..SKY_RINGS {
+            let elevation = FRAC_PI_2 * (ring as f32 + 0.5) / SKY_RINGS as f32;
+            for i in 0..SKY_RING_DIRECTIONS {
+                let azimuth = TAU * i as f32 / SKY_RING_DIRECTIONS as f32;
+                let horizontal = elevation.cos();
+                sky.push(Vec3::new(horizontal * azimuth.cos(), elevation.sin(), horizontal * azimuth.sin()));
+            }
+        }
+
+        Some(ShadowGrid {
+            voxel_size: params.voxel_size.max(0.01),
+            strength: params.shadow_strength,
+            depth: params.shadow_depth as i32,
+            occluders: params.occluders.clone(),
+            sky,
+            shadow: HashMap::new(),
+        })
+    }
+
+    fn cell(&self, point: Vec3) -> IVec3 {
+        (point / self.voxel_size).floor().as_ivec3()
+    }
+
+    /// Record a grown segment at `point`: its cell and a widening pyramid of
+    /// cells below it get darker.
+    pub fn cast(&mut self, point: Vec3) {
+        let cell = self.cell(point);
+        let mut layer_shadow = self.strength;
+        for layer in 0..=self.depth {
+            for x in -layer..=layer {
+                for z in -layer..=layer {
+                    *self.shadow.entry(cell + IVec3::new(x, -layer, z)).or_insert(0.0) += layer_shadow;
+                }
+            }
+            layer_shadow *= SHADOW_FALLOFF;
+        }
+    }
+
+    /// Light reaching a bud at `point`, from 0 (dark) to 1 (open sky). The
+    /// cell above is sampled so a bud is not shaded by its own shoot.
+    pub fn exposure(&self, point: Vec3) -> f32 {
+        if self.blocked(point) {
+            return 0.0;
+        }
+        let above = self.cell(point) + IVec3::Y;
+        let shadow = self.shadow.get(&above).copied().unwrap_or(0.0);
+        (1.0 - shadow).max(0.0) * self.open_sky(point)
+    }
+
+    /// Fraction of the sampled sky directions no occluder hides from `point`.
+    fn open_sky(&self, point: Vec3) -> f32 {
+        if self.occluders.is_empty() {
+            return 1.0;
+        }
+        let open = self
+            .sky
+            .iter()
+            .filter(|&&direction| !self.occluders.iter().any(|occluder| ray_hits(point, direction, occluder)))
+            .count();
+        open as f32 / self.sky.len() as f32
+    }
+
+    /// Whether `point` lies inside one of the occluders.
+    pub fn blocked(&self, point: Vec3) -> bool {
+        self.occluders
+            .iter()
+            .any(|occluder| point.cmpge(occluder.min).all() && point.cmple(occluder.max).all())
+    }
+}
+
+/// Whether a ray from `origin` along `direction` passes through `occluder`
+/// (slab test).
+fn ray_hits(origin: Vec3, direction: Vec3, occluder: &Occluder) -> bool {
+    let mut near = 0.0_f32;
+    let mut far = f32::INFINITY;
+    for axis in 0..3 {
+        let (start, step) = (origin[axis], direction[axis]);
+        let (low, high) = (occluder.min[axis], occluder.max[axis]);
+        if step.abs() < 1e-6 {
+            if start < low || start > high {
+                return false;
+            }
+            continue;
+        }
+        let (a, b) = ((low - start) / step, (high - start) / step);
+        near = near.max(a.min(b));
+        far = far.min(a.max(b));
+        if near > far {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BranchingModel, TreeParameters, generator::ModularTreeGenerator};
+
+    fn grid(occluders: Vec<Occluder>) -> ShadowGrid {
+        ShadowGrid::from_params(&LightParams { enabled: true, occluders, ..LightParams::default() }).unwrap()
+    }
+
+    #[test]
+    fn occluders_shade_the_points_below_them() {
+        let roof = Occluder { min: Vec3::new(-2.0, 4.0, -2.0), max: Vec3::new(2.0, 5.0, 2.0) };
+        let grid = grid(vec![roof]);
+
+        let open = grid.exposure(Vec3::new(30.0, 1.0, 0.0));
+        let under = grid.exposure(Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(open, 1.0);
+        assert!(under < open, "under {} open {}", under, open);
+        assert_eq!(grid.exposure(Vec3::new(0.0, 4.5, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn grown_segments_shade_the_cells_below() {
+        let mut grid = grid(Vec::new());
+        grid.cast(Vec3::new(0.0, 5.0, 0.0));
+
+        let below = grid.exposure(Vec3::new(0.0, 3.0, 0.0));
+        let beside = grid.exposure(Vec3::new(10.0, 3.0, 0.0));
+        let above = grid.exposure(Vec3::new(0.0, 8.0, 0.0));
+        assert!(below < beside, "below {} beside {}", below, beside);
+        assert_eq!(above, 1.0);
+    }
+
+    /// Share of the tree's cross-sections in its lower, inner half, and the
+    /// mean exposure of the limbs there and near the top, measured on the
+    /// shadow the finished tree casts.
+    fn lower_interior(light: bool) -> (f32, f32, f32) {
+        let mut params = TreeParameters::default();
+        params.branching.model = BranchingModel::Growth;
+        params.light.enabled = light;
+        params.roots.enable = false;
+        let tree = ModularTreeGenerator::new().generate_tree(&params);
+
+        let horizontal = |point: Vec3| Vec3::new(point.x, 0.0, point.z).length();
+        let top = tree.cross_sections.iter().map(|cs| cs.center.y).fold(0.0, f32::max);
+        let reach = tree.cross_sections.iter().map(|cs| horizontal(cs.center)).fold(0.0, f32::max);
+        let mut shadows = grid(Vec::new());
+        for cross_section in &tree.cross_sections {
+            shadows.cast(cross_section.center);
+        }
+
+        let limbs: Vec<Vec3> = tree.cross_sections.iter().filter(|cs| cs.depth > 0).map(|cs| cs.center).collect();
+        let mean_exposure = |points: Vec<&Vec3>| points.iter().map(|&&point| shadows.exposure(point)).sum::<f32>() / points.len() as f32;
+        let inside = limbs.iter().filter(|point| point.y < top * 0.5 && horizontal(**point) < reach * 0.5).count();
+        let low = mean_exposure(limbs.iter().filter(|point| point.y < top * 0.5).collect());
+        let high = mean_exposure(limbs.iter().filter(|point| point.y > top * 0.8).collect());
+        (inside as f32 / tree.cross_sections.len() as f32, low, high)
+    }
+
+    #[test]
+    fn light_competition_thins_the_shaded_lower_interior() {
+        let (open_share, _, _) = lower_interior(false);
+        let (share, low, high) = lower_interior(true);
+
+        assert!(low < high * 0.5, "low {} high {}", low, high);
+        assert!(share < open_share, "share {} without light {}", share, open_share);
+    }
+}
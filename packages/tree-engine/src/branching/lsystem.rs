@@ -0,0 +1,284 @@
+use crate::core::{BranchGenerator, BranchingParams, EnvelopeMode, GenerationContext, LSystemParams, LSystemRule, Module, TreeSubsystem};
+use crate::structure::{TreeStructure, BranchCrossSection, ComponentRing, RingType};
+use crate::branching::tropism::tropism_rotation;
+use crate::branching::{BranchingSystem, CrownEnvelope};
+use crate::trunk::RingGenerator;
+use glam::{Vec2, Vec3, Quat};
+use rand::{Rng, rngs::SmallRng};
+
+/// Rewriting stops early once the string grows past this many modules, so a
+/// runaway grammar cannot exhaust memory.
+const MAX_MODULES: usize = 200_000;
+
+/// L-system growth: the axiom is rewritten by the production rules, then a
+/// turtle walks the resulting string and lays down cross-sections.
+///
+/// The grammar comes from `TreeParameters::l_system`. The turtle starts at the
+/// root cross-section with the trunk's radius and splits each drawn segment
+/// into pieces of the trunk segment length so meshes stay as smooth as with
+/// the other models. Depth is the bracket nesting level of a segment, and
+/// tropisms bend every piece as it is drawn. A crown envelope either stops a
+/// branch where it leaves the crown or turns it back inside.
+pub struct LSystemSystem;
+
+#[derive(Clone)]
+struct Turtle {
+    position: Vec3,
+    orientation: Quat, // Local Y is the heading, matching cross-section orientation
+    radius: f32,
+    length: f32,
+    node: usize,       // Cross-section the next segment grows from
+    depth: u32,
+    pruned: bool,      // Left the crown envelope; draws nothing until the branch ends
+}
+
+impl LSystemSystem {
+    pub fn new() -> Self {
+        LSystemSystem
+    }
+
+    /// Apply the production rules `params.iterations` times to the axiom.
+    pub fn expand(params: &LSystemParams, rng: &mut SmallRng) -> Vec<Module> {
+        let mut modules = params.axiom.0.clone();
+        for _ in 0..params.iterations {
+            let mut next = Vec::with_capacity((modules.len() * 2).min(MAX_MODULES));
+            for module in &modules {
+                let successor = match Self::choose_rule(&params.rules, module.symbol, rng) {
+                    Some(rule) => rule.successor.0.as_slice(),
+                    None => std::slice::from_ref(module),
+                };
+                // Checked before growing the string, since a single long
+                // successor can multiply it far past the cap in one pass
+                if next.len() + successor.len() > MAX_MODULES {
+                    return modules;
+                }
+                next.extend_from_slice(successor);
+            }
+            modules = next;
+        }
+        modules
+    }
+
+    fn choose_rule<'a>(rules: &'a [LSystemRule], symbol: char, rng: &mut SmallRng) -> Option<&'a LSystemRule> {
+        let candidates: Vec<&LSystemRule> = rules.iter().filter(|rule| rule.symbol == symbol).collect();
+        match candidates.as_slice() {
+            [] => None,
+            [rule] => Some(rule),
+            _ => {
+                let total: f32 = candidates.iter().map(|rule| rule.probability).sum();
+                if total <= 0.0 {
+                    return None;
+                }
+                let mut pick = rng.gen_range(0.0..total);
+                for rule in &candidates {
+                    if pick < rule.probability {
+                        return Some(rule);
+                    }
+                    pick -= rule.probability;
+                }
+                candidates.last().copied()
+            }
+        }
+    }
+
+    /// Walk the expanded string with a turtle, appending a chain of
+    /// cross-sections for every drawn segment.
+    fn interpret(modules: &[Module], context: &GenerationContext, tree: &mut TreeStructure) {
+        let params = &context.params.l_system;
+        let step = context.params.trunk.segment_length.max(0.01);
+        let max_depth = context.params.general.max_depth;
+        let tropism = &context.params.tropism;
+        let envelope = CrownEnvelope::from_params(&context.params.envelope);
+        let budget = context.params.general.max_cross_sections as usize;
+
+        let ring_generator = RingGenerator::new();
+        let base_rings = tree.cross_sections[0].component_rings.clone();
+        let base_radius = ring_generator.effective_radius(&base_rings);
+
+        let mut turtle = Turtle {
+            position: tree.cross_sections[0].center,
+            orientation: tree.cross_sections[0].orientation,
+            radius: base_radius,
+            length: params.length,
+            node: 0,
+            depth: 0,
+            pruned: false,
+        };
+        let mut stack = Vec::new();
+
+        for module in modules {
+            let angle = module.argument.unwrap_or(params.angle).to_radians();
+            match module.symbol {
+                'F' => {
+                    if turtle.depth >= max_depth || turtle.pruned {
+                        continue;
+                    }
+                    let length = module.argument.unwrap_or(turtle.length);
+                    let pieces = (length / step).ceil().max(1.0) as usize;
+
+                    let component_rings = if turtle.depth == 0 && base_radius > 0.0 {
+                        // The trunk keeps its buttress rings, scaled to the turtle radius
+                        ring_generator.scale_rings(&base_rings, turtle.radius / base_radius)
+                    } else {
+                        vec![ComponentRing {
+                            offset: Vec2::ZERO,
+                            radius: turtle.radius,
+                            ring_type: RingType::SideBranch,
+                        }]
+                    };
+
+                    let piece_length = length / pieces as f32;
+                    for _ in 0..pieces {
+                        if tree.cross_sections.len() >= budget {
+                            tree.budget_exhausted = true;
+                            return;
+                        }
+
+                        let heading = turtle.orientation * Vec3::Y;
+                        turtle.orientation = tropism_rotation(heading, turtle.depth, &context.params.general, tropism) * turtle.orientation;
+
+                        let heading = turtle.orientation * Vec3::Y;
+                        if let Some(envelope) = &envelope
+                            && !envelope.contains(turtle.position + heading * piece_length)
+                        {
+                            match envelope.mode {
+                                EnvelopeMode::Prune => {
+                                    turtle.pruned = true;
+                                    break;
+                                }
+                                EnvelopeMode::Steer => {
+                                    let Some(steered) = envelope.steer(turtle.position, heading, piece_length) else {
+                                        turtle.pruned = true;
+                                        break;
+                                    };
+                                    turtle.orientation = Quat::from_rotation_arc(heading, steered) * turtle.orientation;
+                                }
+                            }
+                        }
+
+                        turtle.position += turtle.orientation * Vec3::Y * piece_length;
+                        let index = tree.cross_sections.len();
+                        tree.cross_sections[turtle.node].children_indices.push(index);
+                        tree.cross_sections.push(BranchCrossSection {
+                            center: turtle.position,
+                            orientation: turtle.orientation,
+                            depth: turtle.depth,
+                            component_rings: component_rings.clone(),
+                            children_indices: Vec::new(),
+                            parent_index: Some(turtle.node),
+                            branch_id: 0,
+                        });
+                        turtle.node = index;
+                    }
+                }
+                '+' => turtle.orientation *= Quat::from_rotation_z(angle),
+                '-' => turtle.orientation *= Quat::from_rotation_z(-angle),
+                '&' => turtle.orientation *= Quat::from_rotation_x(angle),
+                '^' => turtle.orientation *= Quat::from_rotation_x(-angle),
+                '\\' => turtle.orientation *= Quat::from_rotation_y(angle),
+                '/' => turtle.orientation *= Quat::from_rotation_y(-angle),
+                '|' => turtle.orientation *= Quat::from_rotation_z(std::f32::consts::PI),
+                '!' => turtle.radius *= module.argument.unwrap_or(params.radius_scale),
+                '"' => turtle.length *= module.argument.unwrap_or(params.length_scale),
+                '[' => {
+                    stack.push(turtle.clone());
+                    turtle.depth += 1;
+                }
+                ']' => {
+                    // Unbalanced brackets are ignored rather than rejected
+                    if let Some(saved) = stack.pop() {
+                        turtle = saved;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl TreeSubsystem for LSystemSystem {
+    type Params = BranchingParams;
+    type Output = ();
+
+    fn generate(
+        &self,
+        _params: &Self::Params,
+        context: &GenerationContext,
+        tree: &mut TreeStructure,
+        rng: &mut SmallRng,
+    ) -> Self::Output {
+        if tree.cross_sections.is_empty() {
+            return;
+        }
+
+        let modules = Self::expand(&context.params.l_system, rng);
+        Self::interpret(&modules, context, tree);
+    }
+}
+
+impl BranchGenerator for LSystemSystem {
+    /// The grammar describes the whole tree from its axiom and has no rule
+    /// for a single limb, so a grafted limb is a random walk limb.
+    fn graft(&self, tree: &mut TreeStructure, parent: usize, direction: Vec3, context: &GenerationContext, rng: &mut SmallRng) {
+        BranchingSystem::new().graft(tree, parent, direction, context, rng);
+    }
+}
+
+impl Default for LSystemSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Modules;
+    use rand::SeedableRng;
+
+    #[test]
+    fn expansion_stops_at_the_module_cap() {
+        let params = LSystemParams {
+            axiom: Modules::parse("A").unwrap(),
+            rules: vec![LSystemRule { symbol: 'A', successor: Modules::parse("AA").unwrap(), probability: 1.0 }],
+            iterations: 30,
+            ..LSystemParams::default()
+        };
+        let modules = LSystemSystem::expand(&params, &mut SmallRng::seed_from_u64(1));
+
+        // Doubling stops at the last string that still fits
+        assert!(modules.len() <= MAX_MODULES);
+        assert!(modules.len() * 2 > MAX_MODULES);
+    }
+
+    #[test]
+    fn long_successors_stop_at_the_module_cap() {
+        let params = LSystemParams {
+            axiom: Modules::parse("AAAA").unwrap(),
+            rules: vec![LSystemRule { symbol: 'A', successor: Modules::parse(&"A".repeat(1000)).unwrap(), probability: 1.0 }],
+            iterations: 2,
+            ..LSystemParams::default()
+        };
+        let modules = LSystemSystem::expand(&params, &mut SmallRng::seed_from_u64(1));
+
+        // The second pass would make 4 million modules, so the first one is kept
+        assert_eq!(modules.len(), 4000);
+    }
+
+    #[test]
+    fn stochastic_rules_follow_their_weights() {
+        let params = LSystemParams {
+            axiom: Modules::parse(&"A".repeat(1000)).unwrap(),
+            rules: vec![
+                LSystemRule { symbol: 'A', successor: Modules::parse("B").unwrap(), probability: 0.75 },
+                LSystemRule { symbol: 'A', successor: Modules::parse("C").unwrap(), probability: 0.25 },
+            ],
+            iterations: 1,
+            ..LSystemParams::default()
+        };
+        let modules = LSystemSystem::expand(&params, &mut SmallRng::seed_from_u64(1));
+        let b_count = modules.iter().filter(|module| module.symbol == 'B').count();
+        assert_eq!(modules.len(), 1000);
+        assert!((650..850).contains(&b_count), "{} of 1000 modules took the 0.75 rule", b_count);
+    }
+}
@@ -1,79 +1,105 @@
+pub mod envelope;
+pub mod forks;
+pub mod growth;
+pub mod guides;
+pub mod light;
+pub mod lsystem;
 pub mod patterns;
+pub mod space_colonization;
+pub mod tropism;
 
-use crate::core::{BranchGenerator, BranchingParams, TreeSubsystem, GeneralParams, TrunkParams};
-use crate::structure::{TreeStructure, BranchCrossSection, ComponentRing};
+use crate::core::{BranchGenerator, BranchingModel, BranchingParams, GenerationContext, RngStream, TreeSubsystem, GeneralParams, TropismParams, TrunkParams, EnvelopeMode, RadiusModel};
+use crate::structure::{TreeStructure, BranchCrossSection, ComponentRing, RingType};
 use crate::trunk::RingGenerator;
-use glam::{Vec3, Quat};
-use rand::{Rng, rngs::SmallRng};
+use glam::{Vec2, Vec3, Quat};
+use rand::{Rng, RngCore, SeedableRng, rngs::SmallRng};
 
+pub use envelope::CrownEnvelope;
+pub use growth::GrowthSystem;
+pub use guides::{GuidePath, GuideProgress};
+pub use light::ShadowGrid;
+pub use lsystem::LSystemSystem;
 pub use patterns::BranchingPatterns;
+pub use space_colonization::SpaceColonizationSystem;
+
+/// Settings shared by every tip of a random walk generation.
+struct WalkSettings<'a> {
+    branching: &'a BranchingParams,
+    trunk: &'a TrunkParams,
+    general: &'a GeneralParams,
+    tropism: &'a TropismParams,
+    envelope: Option<CrownEnvelope>,
+    guides: Vec<GuidePath>,           // Trunk guide first when there is one, then the limb guides
+    trunk_laterals: bool,             // Whether the trunk grows procedural side branches
+}
+
+/// A branch tip waiting to grow its next segment.
+struct GrowingTip {
+    index: usize,                     // Cross-section the next segment grows from
+    direction: Vec3,
+    depth: u32,
+    segments_since_branch: u32,
+    segments_at_current_depth: u32,
+    branch_nodes: u32,                // Branching nodes already placed along this axis
+    guide: Option<GuideProgress>,     // Set while the axis follows a guide curve
+    rng: SmallRng,
+}
 
 pub struct BranchingSystem {
     patterns: BranchingPatterns,
+    space_colonization: SpaceColonizationSystem,
+    l_system: LSystemSystem,
+    growth: GrowthSystem,
 }
 
 impl BranchingSystem {
     pub fn new() -> Self {
         BranchingSystem {
             patterns: BranchingPatterns::new(),
+            space_colonization: SpaceColonizationSystem::new(),
+            l_system: LSystemSystem::new(),
+            growth: GrowthSystem::new(),
         }
     }
 
-    pub fn generate_branches(
+    /// Grow one segment from `tip`, queueing the tips that carry on from it.
+    /// At most `room` cross-sections are added.
+    fn grow_segment(
         &self,
-        branching_params: &BranchingParams,
-        trunk_params: &TrunkParams,
-        general_params: &GeneralParams,
-        tree: &mut TreeStructure,
-        rng: &mut SmallRng,
-    ) {
-        // Start coordinated generation from root cross-section (trunk)
-        if !tree.cross_sections.is_empty() {
-            Self::generate_coordinated_recursive(
-                &mut tree.cross_sections,
-                0,                        // Start from root cross-section
-                Vec3::new(0.0, 1.0, 0.0), // growth_direction (up)
-                0,                        // depth
-                0,                        // segments_since_branch
-                0,                        // segments_at_current_depth
-                rng,
-                branching_params,
-                trunk_params,
-                general_params,
-            );
-        }
-    }
-
-    fn generate_coordinated_recursive(
         cross_sections: &mut Vec<BranchCrossSection>,
-        current_cross_section_index: usize,
-        growth_direction: Vec3,
-        depth: u32,
-        segments_since_branch: u32,
-        segments_at_current_depth: u32,
-        rng: &mut SmallRng,
-        branching_params: &BranchingParams,
-        trunk_params: &TrunkParams,
-        general_params: &GeneralParams,
+        tip: GrowingTip,
+        settings: &WalkSettings,
+        room: usize,
+        pending: &mut Vec<GrowingTip>,
     ) {
+        let GrowingTip {
+            index: current_cross_section_index,
+            direction: growth_direction,
+            depth,
+            segments_since_branch,
+            segments_at_current_depth,
+            branch_nodes,
+            guide,
+            mut rng,
+        } = tip;
+        let branching_params = settings.branching;
+        let trunk_params = settings.trunk;
+        let general_params = settings.general;
+        let tropism_params = settings.tropism;
+        let envelope = settings.envelope.as_ref();
+
         // Stop recursion if too deep
         if depth >= general_params.max_depth {
             return;
         }
         
+        let curves = &branching_params.curves;
+
         // Stop if branch has extended too many segments at current depth
-        let max_segments_at_depth = match depth {
-            0..=2 => {
-                let min_segments_for_height = (trunk_params.height / trunk_params.segment_length).ceil() as u32;
-                (min_segments_for_height + 10).max(20)
-            },
-            3..=5 => 8,
-            6..=8 => 4,
-            9..=12 => 3,
-            13..=16 => 2,
-            _ => 1,
-        };
-        if segments_at_current_depth >= max_segments_at_depth {
+        let min_segments_for_height = (trunk_params.height / trunk_params.segment_length).ceil() as u32;
+        let trunk_segments = (min_segments_for_height + 10).max(20);
+        let max_segments_at_depth = curves.max_segments_at(depth, general_params, trunk_segments);
+        if guide.is_none() && segments_at_current_depth >= max_segments_at_depth {
             return;
         }
         
@@ -89,57 +115,88 @@ impl BranchingSystem {
         // Stop if trunk is too small
         let total_area: f32 = current_rings.iter().map(|r| std::f32::consts::PI * r.radius * r.radius).sum();
         let effective_trunk_radius = (total_area / std::f32::consts::PI).sqrt();
-        if effective_trunk_radius < 0.005 {
+        if guide.is_none() && effective_trunk_radius < 0.005 {
             return;
         }
         
         // Stop if branch has extended extremely far from trunk center
         let distance_from_trunk = current_center.length();
-        if distance_from_trunk > branching_params.max_reach * 1.5 {
+        if guide.is_none() && distance_from_trunk > branching_params.max_reach * 1.5 {
             return;
         }
         
-        // Add some bend to the growth direction for natural curves
-        let bend_reduction_factor = match depth {
-            0..=1 => 0.1,
-            2..=3 => 0.3,
-            4..=5 => 0.6,
-            _ => 1.0,
+        // A guided axis steps along its curve and ends with it
+        let (next_center, bent_direction, guide) = if let Some(progress) = guide {
+            let path = &settings.guides[progress.path];
+            let Some((distance, next_center, direction)) = path.advance(progress.distance, current_center, trunk_params.segment_length) else {
+                return;
+            };
+            (next_center, direction, Some(GuideProgress { distance, ..progress }))
+        } else {
+            // Add some bend to the growth direction for natural curves
+            let bend_reduction_factor = curves.bend_at(depth, general_params);
+            
+            let bend_min = branching_params.bend_angle_min.min(branching_params.bend_angle_max) * bend_reduction_factor;
+            let bend_max = branching_params.bend_angle_max.max(branching_params.bend_angle_min) * bend_reduction_factor;
+            let bend_angle = rng.gen_range(bend_min..=bend_max).to_radians();
+            let bend_axis = Vec3::new(rng.gen_range(-1.0..=1.0), 0.0, rng.gen_range(-1.0..=1.0)).normalize();
+            let bend_rotation = Quat::from_axis_angle(bend_axis, bend_angle);
+            let bent_direction = (bend_rotation * growth_direction).normalize();
+            let tropism = tropism::tropism_rotation(bent_direction, depth, general_params, tropism_params);
+            let bent_direction = (tropism * bent_direction).normalize();
+            
+            // Apply segment length variation
+            let variation_factor = 1.0 + (rng.gen_range(-1.0..=1.0) * trunk_params.segment_length_variation);
+            let varied_segment_length = trunk_params.segment_length * variation_factor.max(0.1);
+            
+            let mut next_center = current_center + bent_direction * varied_segment_length;
+            
+            // Keep the limb inside the crown envelope
+            let mut bent_direction = bent_direction;
+            if let Some(envelope) = envelope
+                && !envelope.contains(next_center)
+            {
+                match envelope.mode {
+                    EnvelopeMode::Prune => return,
+                    EnvelopeMode::Steer => {
+                        let Some(steered) = envelope.steer(current_center, bent_direction, varied_segment_length) else {
+                            return;
+                        };
+                        bent_direction = steered;
+                        next_center = current_center + bent_direction * varied_segment_length;
+                    }
+                }
+            }
+            (next_center, bent_direction, None)
         };
-        
-        let bend_min = branching_params.bend_angle_min.min(branching_params.bend_angle_max) * bend_reduction_factor;
-        let bend_max = branching_params.bend_angle_max.max(branching_params.bend_angle_min) * bend_reduction_factor;
-        let bend_angle = rng.gen_range(bend_min..=bend_max).to_radians();
-        let bend_axis = Vec3::new(rng.gen_range(-1.0..=1.0), 0.0, rng.gen_range(-1.0..=1.0)).normalize();
-        let bend_rotation = Quat::from_axis_angle(bend_axis, bend_angle);
-        let bent_direction = (bend_rotation * growth_direction).normalize();
-        
-        // Apply segment length variation
-        let variation_factor = 1.0 + (rng.gen_range(-1.0..=1.0) * trunk_params.segment_length_variation);
-        let varied_segment_length = trunk_params.segment_length * variation_factor.max(0.1);
-        
-        let next_center = current_center + bent_direction * varied_segment_length;
         let next_height = next_center.y;
         
         // Make coordinated branching decision
         let freq_min = branching_params.frequency_min.max(1);
         let freq_max = branching_params.frequency_max.max(freq_min);
-        let segment_branch_ready = segments_since_branch >= rng.gen_range(freq_min..=freq_max);
+        // Nodes carrying several branches are spaced further apart, keeping the
+        // number of side branches per segment independent of the phyllotaxis
+        let frequency = (rng.gen_range(freq_min..=freq_max) as f32 * curves.frequency_at(depth, general_params)).round().max(1.0) as u32;
+        let node_spacing = frequency * self.patterns.branches_per_node(branching_params);
+        let segment_branch_ready = segments_since_branch >= node_spacing;
         let height_allows_branching = next_height >= trunk_params.split_height;
-        let should_branch = segment_branch_ready && height_allows_branching && depth < general_params.max_depth - 1;
+        let laterals_allowed = depth > 0 || settings.trunk_laterals;
+        let should_branch = segment_branch_ready && height_allows_branching && laterals_allowed && depth < general_params.max_depth - 1;
         
         if should_branch {
-            Self::create_coordinated_branches(
+            self.create_coordinated_branches(
                 cross_sections,
                 current_cross_section_index,
                 &current_rings,
                 next_center,
                 bent_direction,
                 depth,
-                rng,
-                branching_params,
-                trunk_params,
-                general_params,
+                branch_nodes,
+                guide,
+                &mut rng,
+                settings,
+                room,
+                pending,
             );
         } else {
             // Continue as trunk - create next cross-section with all rings
@@ -147,7 +204,7 @@ impl BranchingSystem {
             let buttressing_factor = if ring_count > 2.0 { 0.5 } else { 1.0 };
             let base_taper_factor = if next_height < trunk_params.height { 0.05 } else { 0.4 };
             let segment_taper_factor = base_taper_factor * buttressing_factor;
-            let segment_taper = 1.0 - (1.0 - branching_params.radius_taper) * segment_taper_factor;
+            let segment_taper = 1.0 - (1.0 - branching_params.radius_taper) * segment_taper_factor * curves.taper_at(depth, general_params);
             
             // Create child rings for ALL parent rings
             let ring_generator = RingGenerator::new();
@@ -168,7 +225,7 @@ impl BranchingSystem {
                         1.0
                     };
                     
-                    child_ring.offset = child_ring.offset * height_factor;
+                    child_ring.offset *= height_factor;
                 }
                 
                 child_rings.push(child_ring);
@@ -181,159 +238,253 @@ impl BranchingSystem {
                 depth,
                 component_rings: child_rings,
                 children_indices: Vec::new(),
+                parent_index: Some(current_cross_section_index),
+                branch_id: 0,
             };
             
             let new_cross_section_index = cross_sections.len();
             cross_sections[current_cross_section_index].children_indices.push(new_cross_section_index);
             cross_sections.push(new_cross_section);
             
-            // Continue recursively with the new cross-section
-            Self::generate_coordinated_recursive(
-                cross_sections,
-                new_cross_section_index,
-                bent_direction,
+            // Continue from the new cross-section
+            pending.push(GrowingTip {
+                index: new_cross_section_index,
+                direction: bent_direction,
                 depth,
-                segments_since_branch + 1,
-                segments_at_current_depth + 1,
+                segments_since_branch: segments_since_branch + 1,
+                segments_at_current_depth: segments_at_current_depth + 1,
+                branch_nodes,
+                guide,
                 rng,
-                branching_params,
-                trunk_params,
-                general_params,
-            );
+            });
         }
     }
     
+    /// Split the current axis at a branching node. Usually the axis continues
+    /// and the phyllotaxis pattern decides how many side branches emerge and
+    /// where; a co-dominant fork instead ends the axis in several equal stems.
+    /// `branch_nodes` counts the nodes already placed along this axis. A
+    /// guided axis never forks co-dominantly and carries on along its guide.
+    /// Only the first `room` children are grown, so a whorl or a many-way
+    /// fork cannot overrun the cross-section budget.
+    #[allow(clippy::too_many_arguments)]
     fn create_coordinated_branches(
+        &self,
         cross_sections: &mut Vec<BranchCrossSection>,
         parent_cross_section_index: usize,
         parent_rings: &[ComponentRing],
         center: Vec3,
         main_direction: Vec3,
         depth: u32,
+        branch_nodes: u32,
+        guide: Option<GuideProgress>,
         rng: &mut SmallRng,
-        branching_params: &BranchingParams,
-        trunk_params: &TrunkParams,
-        general_params: &GeneralParams,
+        settings: &WalkSettings,
+        room: usize,
+        pending: &mut Vec<GrowingTip>,
     ) {
-        // Create branch direction with 3D spherical branching
-        let angle_min = branching_params.angle_min.min(branching_params.angle_max);
-        let angle_max = branching_params.angle_max.max(branching_params.angle_min);
-        let branch_angle = rng.gen_range(angle_min..=angle_max).to_radians();
+        let branching_params = settings.branching;
+        let trunk_params = settings.trunk;
+        let curves = &branching_params.curves;
+        let codominant = guide.is_none()
+            && branching_params.codominant_probability > 0.0
+            && rng.gen_bool(branching_params.codominant_probability.min(1.0) as f64);
         
-        // Traditional 2D branching (base method)
-        let up_component = Vec3::Y;
-        let perpendicular = if main_direction.cross(up_component).length() > 0.1 {
-            main_direction.cross(up_component).normalize()
+        let mut children = if codominant {
+            forks::codominant_children(main_direction, branching_params, rng)
         } else {
-            Vec3::X
+            // Create branch direction with 3D spherical branching; further
+            // branches of a pair or whorl pick their own angle
+            let angle_scale = curves.angle_at(depth + 1, settings.general);
+            let first_angle = forks::branch_angle(branching_params, rng) * angle_scale;
+            let spread = curves.azimuth_spread_at(depth + 1, settings.general);
+            let azimuths = self.patterns.side_branch_azimuths(branching_params, branch_nodes, spread, rng);
+            let angles: Vec<f32> = std::iter::once(first_angle)
+                .chain((1..azimuths.len()).map(|_| forks::branch_angle(branching_params, rng) * angle_scale))
+                .collect();
+            forks::lateral_children(main_direction, &angles, &azimuths)
         };
-        let planar_rotation = Quat::from_axis_angle(perpendicular, branch_angle);
-        let planar_direction = (planar_rotation * main_direction).normalize();
         
-        // Apply 3D spherical branching based on azimuth variation parameter
-        let branch_direction = if branching_params.azimuth_variation > 0.0 {
-            let azimuth_angle = rng.gen_range(0.0..2.0 * std::f32::consts::PI) * branching_params.azimuth_variation;
-            let azimuth_rotation = Quat::from_axis_angle(main_direction, azimuth_angle);
-            (azimuth_rotation * planar_direction).normalize()
-        } else {
-            planar_direction
-        };
-        
-        // Create trunk continuation and branch cross-sections
-        let segment_taper_factor = 0.15;
-        let segment_taper = 1.0 - (1.0 - branching_params.radius_taper) * segment_taper_factor;
-        
-        let ring_generator = RingGenerator::new();
-        let mut trunk_rings = Vec::new();
-        let mut branch_rings = Vec::new();
-        
-        // Split rings: some continue as trunk, one becomes branch
-        for (i, parent_ring) in parent_rings.iter().enumerate() {
-            if parent_rings.len() == 1 {
-                // Single ring: trunk gets the ring, branch gets a smaller copy
-                let trunk_child = ring_generator.create_child_ring_from_parent(
-                    parent_ring,
-                    segment_taper * 0.95
-                );
-                let branch_child = ring_generator.create_child_ring_from_parent(
-                    parent_ring,
-                    segment_taper * 0.8
-                );
-                trunk_rings.push(trunk_child);
-                branch_rings.push(branch_child);
-            } else {
-                // Multiple rings: split them between trunk and branch
-                if i == parent_rings.len() - 1 {
-                    // Last ring becomes the branch
-                    let branch_child = ring_generator.create_child_ring_from_parent(
-                        parent_ring,
-                        segment_taper
-                    );
-                    branch_rings.push(branch_child);
-                } else {
-                    // Other rings continue as trunk
-                    let trunk_child = ring_generator.create_child_ring_from_parent(
-                        parent_ring,
-                        segment_taper * 0.95
-                    );
-                    trunk_rings.push(trunk_child);
+        // The continuing axis steps along the guide instead of straight on
+        let mut child_centers: Vec<Vec3> = children.iter().map(|child| center + child.direction * trunk_params.segment_length).collect();
+        let mut child_guides = vec![None; children.len()];
+        if let Some(progress) = guide
+            && let Some(axis) = children.iter().position(|child| child.continues_axis)
+        {
+            let path = &settings.guides[progress.path];
+            match path.advance(progress.distance, center, trunk_params.segment_length) {
+                Some((distance, point, direction)) => {
+                    children[axis].direction = direction;
+                    child_centers[axis] = point;
+                    child_guides[axis] = Some(GuideProgress { distance, ..progress });
                 }
+                // The guide ends here, and so does the axis
+                None => return,
             }
         }
         
-        // Create trunk continuation cross-section
-        let trunk_cross_section = BranchCrossSection {
-            center: center + main_direction * trunk_params.segment_length,
-            orientation: Quat::from_rotation_arc(Vec3::Y, main_direction),
-            depth: depth + 1,
-            component_rings: trunk_rings,
-            children_indices: Vec::new(),
-        };
+        // Children stepping out of the crown envelope are steered back like any
+        // other segment; in prune mode, or if steering fails, they never start
+        let envelope = settings.envelope.as_ref();
+        if let Some(envelope) = envelope
+            && envelope.mode == EnvelopeMode::Steer
+        {
+            for ((child, child_center), child_guide) in children.iter_mut().zip(&mut child_centers).zip(&child_guides) {
+                if child_guide.is_none()
+                    && !envelope.contains(*child_center)
+                    && let Some(steered) = envelope.steer(center, child.direction, trunk_params.segment_length)
+                {
+                    child.direction = steered;
+                    *child_center = center + steered * trunk_params.segment_length;
+                }
+            }
+        }
+        let inside: Vec<bool> = child_centers
+            .iter()
+            .zip(&child_guides)
+            .map(|(child_center, child_guide)| child_guide.is_some() || envelope.is_none_or(|envelope| envelope.contains(*child_center)))
+            .collect();
         
-        // Create branch cross-section
-        let branch_cross_section = BranchCrossSection {
-            center: center + branch_direction * trunk_params.segment_length,
-            orientation: Quat::from_rotation_arc(Vec3::Y, branch_direction),
-            depth: depth + 1,
-            component_rings: branch_rings,
-            children_indices: Vec::new(),
+        // Create child cross-sections, splitting the parent's rings between them
+        let child_rings = match branching_params.radius_model {
+            RadiusModel::Fixed => {
+                let segment_taper_factor = 0.15;
+                let segment_taper = 1.0 - (1.0 - branching_params.radius_taper) * segment_taper_factor * curves.taper_at(depth, settings.general);
+                forks::split_rings(parent_rings, &children, segment_taper)
+            }
+            RadiusModel::PipeModel => {
+                // The fork itself conserves r^n; only the segments taper
+                forks::apply_pipe_model(&mut children, branching_params);
+                forks::pipe_rings(parent_rings, &children)
+            }
         };
         
-        let trunk_cs_idx = cross_sections.len();
-        let branch_cs_idx = trunk_cs_idx + 1;
-        
-        cross_sections[parent_cross_section_index].children_indices.push(trunk_cs_idx);
-        cross_sections[parent_cross_section_index].children_indices.push(branch_cs_idx);
+        // Each child limb gets its own RNG seeded from this fork, so the shape of
+        // one limb never depends on how many draws its sibling consumed
+        let child_rngs: Vec<SmallRng> = children
+            .iter()
+            .map(|_| SmallRng::seed_from_u64(rng.next_u64()))
+            .collect();
         
-        cross_sections.push(trunk_cross_section);
-        cross_sections.push(branch_cross_section);
+        let grown: Vec<_> = children
+            .into_iter()
+            .zip(child_rings)
+            .zip(child_centers)
+            .zip(child_rngs)
+            .zip(child_guides)
+            .zip(inside)
+            .filter_map(|(((((child, rings), child_center), child_rng), child_guide), inside)| {
+                inside.then_some((child, rings, child_center, child_rng, child_guide))
+            })
+            .take(room)
+            .collect();
         
-        // Continue both trunk and branch recursively
-        Self::generate_coordinated_recursive(
-            cross_sections,
-            trunk_cs_idx,
-            main_direction,
-            depth + 1,
-            0,
-            0,
-            rng,
-            branching_params,
-            trunk_params,
-            general_params,
-        );
+        let first_child_index = cross_sections.len();
+        for (child, component_rings, child_center, _, _) in &grown {
+            let child_index = cross_sections.len();
+            cross_sections[parent_cross_section_index].children_indices.push(child_index);
+            cross_sections.push(BranchCrossSection {
+                center: *child_center,
+                orientation: Quat::from_rotation_arc(Vec3::Y, child.direction),
+                depth: depth + 1,
+                component_rings: component_rings.clone(),
+                children_indices: Vec::new(),
+                parent_index: Some(parent_cross_section_index),
+                branch_id: 0,
+            });
+        }
         
-        Self::generate_coordinated_recursive(
-            cross_sections,
-            branch_cs_idx,
-            branch_direction,
-            depth + 1,
-            0,
-            0,
-            rng,
-            branching_params,
-            trunk_params,
-            general_params,
-        );
+        // Queue every child, last first so the first child grows next; only
+        // the continuing axis keeps counting nodes for the phyllotaxis pattern
+        for (offset, (child, _, _, child_rng, child_guide)) in grown.into_iter().enumerate().rev() {
+            pending.push(GrowingTip {
+                index: first_child_index + offset,
+                direction: child.direction,
+                depth: depth + 1,
+                segments_since_branch: 0,
+                segments_at_current_depth: 0,
+                branch_nodes: if child.continues_axis { branch_nodes + 1 } else { 0 },
+                guide: child_guide,
+                rng: child_rng,
+            });
+        }
+    }
+    
+    /// First cross-section of a limb leaving `parent` at `center`, sized as a
+    /// lone side branch of the parent's thickest ring. Returns its index.
+    fn start_side_limb(
+        cross_sections: &mut Vec<BranchCrossSection>,
+        parent: usize,
+        center: Vec3,
+        direction: Vec3,
+        depth: u32,
+    ) -> usize {
+        let parent_radius = cross_sections[parent].component_rings.iter().map(|ring| ring.radius).fold(0.0, f32::max);
+        let index = cross_sections.len();
+        cross_sections[parent].children_indices.push(index);
+        cross_sections.push(BranchCrossSection {
+            center,
+            orientation: Quat::from_rotation_arc(Vec3::Y, direction),
+            depth,
+            component_rings: vec![ComponentRing {
+                offset: Vec2::ZERO,
+                radius: parent_radius * forks::SIDE_BRANCH_SHARE,
+                ring_type: RingType::SideBranch,
+            }],
+            children_indices: Vec::new(),
+            parent_index: Some(parent),
+            branch_id: 0,
+        });
+        index
+    }
+    
+    /// First cross-section of the limb following guide `path`, leaving the
+    /// trunk cross-section nearest the guide's start. Returns the tip that
+    /// walks the rest of the guide.
+    /// The trunk is the chain of first children from the root, since a fork
+    /// lists the continuing axis first.
+    fn start_guided_limb(
+        cross_sections: &mut Vec<BranchCrossSection>,
+        path: usize,
+        settings: &WalkSettings,
+        rng: &mut SmallRng,
+    ) -> Option<GrowingTip> {
+        let guide = &settings.guides[path];
+        let start = guide.start();
+        let trunk = std::iter::successors(Some(0), |&index| cross_sections[index].children_indices.first().copied());
+        let parent = trunk.min_by(|&a, &b| {
+            let distance = |index: usize| cross_sections[index].center.distance_squared(start);
+            distance(a).total_cmp(&distance(b))
+        })?;
+
+        let parent_center = cross_sections[parent].center;
+        let (distance, center, direction) = guide.advance(0.0, parent_center, settings.trunk.segment_length)?;
+        let index = Self::start_side_limb(cross_sections, parent, center, direction, 1);
+
+        Some(GrowingTip {
+            index,
+            direction,
+            depth: 1,
+            segments_since_branch: 0,
+            segments_at_current_depth: 0,
+            branch_nodes: 0,
+            guide: Some(GuideProgress { path, distance }),
+            rng: SmallRng::seed_from_u64(rng.next_u64()),
+        })
+    }
+    
+    /// Grow tips until none are left. Returns false if the cross-section
+    /// budget ran out first.
+    fn walk(&self, tree: &mut TreeStructure, pending: &mut Vec<GrowingTip>, settings: &WalkSettings, budget: usize) -> bool {
+        while let Some(tip) = pending.pop() {
+            if tree.cross_sections.len() >= budget {
+                tree.budget_exhausted = true;
+                return false;
+            }
+            let room = budget - tree.cross_sections.len();
+            self.grow_segment(&mut tree.cross_sections, tip, settings, room, pending);
+        }
+        true
     }
 }
 
@@ -342,19 +493,127 @@ impl TreeSubsystem for BranchingSystem {
     type Output = ();
     
     fn generate(
-        _params: &Self::Params,
-        _tree: &mut TreeStructure,
-        _rng: &mut SmallRng,
+        &self,
+        params: &Self::Params,
+        context: &GenerationContext,
+        tree: &mut TreeStructure,
+        rng: &mut SmallRng,
     ) -> Self::Output {
-        // Note: Branching is complex and will be handled by the orchestrator
-        // This trait implementation is mainly for interface consistency
+        match params.model {
+            BranchingModel::RandomWalk => {}
+            BranchingModel::SpaceColonization => return self.space_colonization.generate(params, context, tree, rng),
+            BranchingModel::LSystem => return self.l_system.generate(params, context, tree, rng),
+            BranchingModel::Growth => return self.growth.generate(params, context, tree, rng),
+        }
+
+        if tree.cross_sections.is_empty() {
+            return;
+        }
+
+        let guide_params = &context.params.guides;
+        let trunk_guide = guide_params.trunk.as_ref().and_then(GuidePath::from_curve);
+        let trunk_guided = trunk_guide.is_some();
+        let limb_guides: Vec<GuidePath> = guide_params.branches.iter().filter_map(GuidePath::from_curve).collect();
+        let settings = WalkSettings {
+            branching: params,
+            trunk: &context.params.trunk,
+            general: &context.params.general,
+            tropism: &context.params.tropism,
+            envelope: CrownEnvelope::from_params(&context.params.envelope),
+            trunk_laterals: limb_guides.is_empty() || guide_params.keep_procedural_limbs,
+            guides: trunk_guide.into_iter().chain(limb_guides).collect(),
+        };
+
+        // Start coordinated generation from root cross-section (trunk). Tips
+        // are taken last in, first out, so each limb is finished before its
+        // younger siblings, and generation stops once the budget is spent.
+        let budget = context.params.general.max_cross_sections as usize;
+        let mut pending = vec![GrowingTip {
+            index: 0,
+            direction: Vec3::Y,
+            depth: 0,
+            segments_since_branch: 0,
+            segments_at_current_depth: 0,
+            branch_nodes: 0,
+            guide: trunk_guided.then_some(GuideProgress { path: 0, distance: 0.0 }),
+            rng: rng.clone(),
+        }];
+        // The root tip hands its stream down to every procedural branch, so
+        // guided limbs draw from a stream of their own
+        let mut limb_rng = context.rng(RngStream::GuidedLimbs);
+        let mut next_limb = usize::from(trunk_guided);
+        while self.walk(tree, &mut pending, &settings, budget) && next_limb < settings.guides.len() {
+            if tree.cross_sections.len() >= budget {
+                tree.budget_exhausted = true;
+                break;
+            }
+            // Guided limbs grow one after another once the trunk stands
+            pending.extend(Self::start_guided_limb(&mut tree.cross_sections, next_limb, &settings, &mut limb_rng));
+            next_limb += 1;
+        }
     }
 }
 
-impl BranchGenerator for BranchingSystem {}
+impl BranchGenerator for BranchingSystem {
+    /// Graft a random walk limb onto cross-section `parent`, leaving along
+    /// `direction`. The limb is one order deeper than its parent and grows
+    /// with the current branching, tropism and envelope settings whatever
+    /// the tree's own branching model.
+    fn graft(&self, tree: &mut TreeStructure, parent: usize, direction: Vec3, context: &GenerationContext, rng: &mut SmallRng) {
+        let Some(parent_cross_section) = tree.cross_sections.get(parent) else {
+            return;
+        };
+        let direction = direction.normalize_or(Vec3::Y);
+        let depth = parent_cross_section.depth + 1;
+        let center = parent_cross_section.center + direction * context.params.trunk.segment_length;
+
+        let settings = WalkSettings {
+            branching: &context.params.branching,
+            trunk: &context.params.trunk,
+            general: &context.params.general,
+            tropism: &context.params.tropism,
+            envelope: CrownEnvelope::from_params(&context.params.envelope),
+            guides: Vec::new(),
+            trunk_laterals: true,
+        };
+        let index = Self::start_side_limb(&mut tree.cross_sections, parent, center, direction, depth);
+        let mut pending = vec![GrowingTip {
+            index,
+            direction,
+            depth,
+            segments_since_branch: 0,
+            segments_at_current_depth: 0,
+            branch_nodes: 0,
+            guide: None,
+            rng: SmallRng::seed_from_u64(rng.next_u64()),
+        }];
+        self.walk(tree, &mut pending, &settings, context.params.general.max_cross_sections as usize);
+    }
+}
 
 impl Default for BranchingSystem {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::core::{Phyllotaxis, TreeParameters, generator::ModularTreeGenerator};
+
+    #[test]
+    fn whorls_and_forks_stay_within_the_budget() {
+        for budget in 100..130 {
+            let mut params = TreeParameters::default();
+            params.general.max_cross_sections = budget;
+            params.branching.phyllotaxis = Phyllotaxis::Whorled;
+            params.branching.whorl_count = 6;
+            params.branching.codominant_probability = 0.3;
+            params.branching.codominant_count = 5;
+            params.roots.enable = false;
+            let tree = ModularTreeGenerator::new().generate_tree(&params);
+
+            assert!(tree.cross_sections.len() <= budget as usize, "{} cross-sections for a budget of {}", tree.cross_sections.len(), budget);
+            assert!(tree.budget_exhausted);
+        }
+    }
+}
@@ -1,7 +1,12 @@
-use crate::core::{BranchingParams, GeneralParams};
-use crate::structure::TreeStructure;
-use rand::rngs::SmallRng;
+use crate::core::{BranchingParams, Phyllotaxis};
+use rand::{Rng, rngs::SmallRng};
+use std::f32::consts::{PI, TAU};
 
+/// Largest random offset applied to patterned azimuths, reached at
+/// `azimuth_variation = 1.0`
+const MAX_PATTERN_JITTER: f32 = PI / 4.0;
+
+/// Phyllotaxis: decides where around the parent axis side branches emerge.
 pub struct BranchingPatterns;
 
 impl BranchingPatterns {
@@ -9,19 +14,61 @@ impl BranchingPatterns {
         BranchingPatterns
     }
 
-    pub fn apply_branching_pattern(
-        &self,
-        _params: &BranchingParams,
-        _general: &GeneralParams,
-        _tree: &mut TreeStructure,
-        _rng: &mut SmallRng,
-    ) {
-        // TODO: Extract the complex branching logic from tree.rs
-        // This includes:
-        // - generate_coordinated_recursive
-        // - create_coordinated_branches
-        // - Branching decision logic
-        // - 3D spherical branching
-        // - Ring splitting between trunk and branch
+    /// Number of side branches emerging at each branching node.
+    pub fn branches_per_node(&self, params: &BranchingParams) -> u32 {
+        match params.phyllotaxis {
+            Phyllotaxis::Random | Phyllotaxis::Alternate | Phyllotaxis::Spiral => 1,
+            Phyllotaxis::Opposite | Phyllotaxis::Decussate => 2,
+            Phyllotaxis::Whorled => params.whorl_count.max(1),
+        }
+    }
+
+    /// Azimuths, in radians around the parent axis, of the side branches that
+    /// grow from the `node`-th branching node along an axis. The length of the
+    /// result is the number of side branches at that node. `spread` scales
+    /// `azimuth_variation`.
+    pub fn side_branch_azimuths(&self, params: &BranchingParams, node: u32, spread: f32, rng: &mut SmallRng) -> Vec<f32> {
+        let node = node as f32;
+        let variation = params.azimuth_variation * spread;
+        let azimuths = match params.phyllotaxis {
+            Phyllotaxis::Random => {
+                // Original behaviour: no pattern, just a random spread
+                let azimuth = if variation > 0.0 {
+                    rng.gen_range(0.0..TAU) * variation
+                } else {
+                    0.0
+                };
+                return vec![azimuth];
+            }
+            Phyllotaxis::Alternate => vec![PI * (node % 2.0)],
+            Phyllotaxis::Opposite => vec![0.0, PI],
+            Phyllotaxis::Decussate => {
+                let turn = PI / 2.0 * (node % 2.0);
+                vec![turn, turn + PI]
+            }
+            Phyllotaxis::Whorled => {
+                // Successive whorls are offset by half the spacing so branches
+                // do not stack directly above each other
+                let count = self.branches_per_node(params);
+                let spacing = TAU / count as f32;
+                let offset = spacing * 0.5 * (node % 2.0);
+                (0..count).map(|i| offset + spacing * i as f32).collect()
+            }
+            Phyllotaxis::Spiral => vec![(node * params.divergence_angle.to_radians()) % TAU],
+        };
+
+        if variation <= 0.0 {
+            return azimuths;
+        }
+        azimuths
+            .into_iter()
+            .map(|azimuth| azimuth + rng.gen_range(-1.0..=1.0) * variation * MAX_PATTERN_JITTER)
+            .collect()
+    }
+}
+
+impl Default for BranchingPatterns {
+    fn default() -> Self {
+        Self::new()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,288 @@
+use crate::core::{BranchGenerator, BranchingParams, GenerationContext, SpaceColonizationParams, TreeSubsystem};
+use crate::structure::{TreeStructure, BranchCrossSection, ComponentRing, RingType};
+use crate::branching::{BranchingSystem, CrownEnvelope};
+use crate::trunk::RingGenerator;
+use glam::{Vec2, Vec3, Quat};
+use rand::{Rng, rngs::SmallRng};
+
+/// Space colonization growth: attraction points scattered through an
+/// ellipsoidal crown pull the nearest branch tips toward them until every
+/// point has been reached or the iteration budget runs out. When a crown
+/// envelope is selected the points fill that shape instead.
+///
+/// Settings come from `TreeParameters::space_colonization`; the trunk's
+/// segment length is used as the growth step so both models produce
+/// similarly dense cross-sections.
+pub struct SpaceColonizationSystem;
+
+/// Radius of a branch tip; thicker segments grow from it by the pipe model.
+const TIP_RADIUS: f32 = 0.02;
+
+impl SpaceColonizationSystem {
+    pub fn new() -> Self {
+        SpaceColonizationSystem
+    }
+
+    fn sample_attraction_points(
+        params: &SpaceColonizationParams,
+        crown_base: f32,
+        rng: &mut SmallRng,
+    ) -> Vec<Vec3> {
+        let half_height = params.crown_height * 0.5;
+        let crown_center = Vec3::new(0.0, crown_base + half_height, 0.0);
+        let scale = Vec3::new(params.crown_radius, half_height, params.crown_radius);
+
+        let mut points = Vec::with_capacity(params.attraction_points as usize);
+        while points.len() < params.attraction_points as usize {
+            // Rejection sample the unit sphere, then stretch it into the crown
+            let candidate = Vec3::new(
+                rng.gen_range(-1.0..=1.0),
+                rng.gen_range(-1.0..=1.0),
+                rng.gen_range(-1.0..=1.0),
+            );
+            if candidate.length_squared() <= 1.0 {
+                points.push(crown_center + candidate * scale);
+            }
+        }
+        points
+    }
+
+    fn sample_envelope_points(
+        params: &SpaceColonizationParams,
+        envelope: &CrownEnvelope,
+        rng: &mut SmallRng,
+    ) -> Vec<Vec3> {
+        let (bottom, top) = envelope.vertical_range();
+        let radius = envelope.max_radius();
+        let target = params.attraction_points as usize;
+
+        // Rejection sample the bounding cylinder; give up on (nearly) empty
+        // shapes rather than loop forever
+        let mut points = Vec::with_capacity(target);
+        for _ in 0..target * 100 {
+            if points.len() == target {
+                break;
+            }
+            let candidate = Vec3::new(
+                rng.gen_range(-radius..=radius),
+                rng.gen_range(bottom..=top),
+                rng.gen_range(-radius..=radius),
+            );
+            if envelope.contains(candidate) {
+                points.push(candidate);
+            }
+        }
+        points
+    }
+
+    /// Assign depths and ring radii once the skeleton is complete. Depth
+    /// increases at every fork, like the random walk model, and radii follow
+    /// the pipe model: a segment's `r^pipe_exponent` is proportional to the
+    /// number of tips it supports. The trunk below the first fork blends
+    /// from the buttressed base rings into the pipe-model radius.
+    fn finish_cross_sections(tree: &mut TreeStructure, first_grown: usize, pipe_exponent: f32) {
+        let cross_sections = &mut tree.cross_sections;
+        let count = cross_sections.len();
+
+        let mut tip_counts = vec![0u32; count];
+        for index in (0..count).rev() {
+            let children = &cross_sections[index].children_indices;
+            tip_counts[index] = children.iter().map(|&child| tip_counts[child]).sum::<u32>().max(1);
+        }
+
+        for index in 0..count {
+            let depth = cross_sections[index].depth;
+            let fork = cross_sections[index].children_indices.len() > 1;
+            for child in cross_sections[index].children_indices.clone() {
+                cross_sections[child].depth = if fork { depth + 1 } else { depth };
+            }
+        }
+
+        let ring_generator = RingGenerator::new();
+        let base_rings = cross_sections[0].component_rings.clone();
+        let base_radius = ring_generator.effective_radius(&base_rings);
+        let base_height = cross_sections[0].center.y;
+        let trunk_top = cross_sections[first_grown..]
+            .iter()
+            .filter(|cross_section| cross_section.depth == 0)
+            .map(|cross_section| cross_section.center.y)
+            .fold(base_height, f32::max);
+
+        for index in first_grown..count {
+            let radius = TIP_RADIUS * (tip_counts[index] as f32).powf(1.0 / pipe_exponent.max(1.0));
+            let cross_section = &mut cross_sections[index];
+            cross_section.component_rings = if cross_section.depth == 0 && base_radius > 0.0 {
+                // The trunk keeps its buttress rings, shrinking toward the crown
+                let height = (cross_section.center.y - base_height) / (trunk_top - base_height).max(f32::EPSILON);
+                let ratio = 1.0 + (radius / base_radius - 1.0) * height.clamp(0.0, 1.0);
+                ring_generator.scale_rings(&base_rings, ratio)
+            } else {
+                vec![ComponentRing {
+                    offset: Vec2::ZERO,
+                    radius,
+                    ring_type: RingType::SideBranch,
+                }]
+            };
+        }
+    }
+
+    fn push_node(tree: &mut TreeStructure, parent: usize, center: Vec3, direction: Vec3) -> usize {
+        let index = tree.cross_sections.len();
+        tree.cross_sections[parent].children_indices.push(index);
+        tree.cross_sections.push(BranchCrossSection {
+            center,
+            orientation: Quat::from_rotation_arc(Vec3::Y, direction),
+            depth: 0, // Assigned once the skeleton is complete
+            component_rings: Vec::new(),
+            children_indices: Vec::new(),
+            parent_index: Some(parent),
+            branch_id: 0,
+        });
+        index
+    }
+}
+
+impl TreeSubsystem for SpaceColonizationSystem {
+    type Params = BranchingParams;
+    type Output = ();
+
+    fn generate(
+        &self,
+        _params: &Self::Params,
+        context: &GenerationContext,
+        tree: &mut TreeStructure,
+        rng: &mut SmallRng,
+    ) -> Self::Output {
+        if tree.cross_sections.is_empty() {
+            return;
+        }
+
+        let params = &context.params.space_colonization;
+        let step = context.params.trunk.segment_length.max(0.01);
+        let (crown_top, points) = match CrownEnvelope::from_params(&context.params.envelope) {
+            Some(envelope) => (envelope.vertical_range().1, Self::sample_envelope_points(params, &envelope, rng)),
+            None => {
+                let crown_base = context.params.trunk.split_height;
+                let points = Self::sample_attraction_points(params, crown_base, rng);
+                (crown_base + params.crown_height, points)
+            }
+        };
+
+        // Growth starts from the root cross-section placed by the trunk stage
+        let first_grown = tree.cross_sections.len();
+        let mut directions = vec![Vec3::Y; first_grown];
+        let mut leader = 0;
+        let mut colonizing = false;
+
+        // Closest node and distance for every attraction point still alive
+        let root_center = tree.cross_sections[0].center;
+        let mut closest: Vec<Option<(usize, f32)>> = points
+            .iter()
+            .map(|point| Some((0, point.distance(root_center))))
+            .collect();
+
+        let budget = context.params.general.max_cross_sections as usize;
+        for _ in 0..params.iterations {
+            if tree.cross_sections.len() >= budget {
+                tree.budget_exhausted = true;
+                break;
+            }
+
+            let mut pull = vec![Vec3::ZERO; tree.cross_sections.len()];
+            let mut attracted = false;
+            for (point, nearest) in points.iter().zip(&closest) {
+                if let Some((node, distance)) = *nearest
+                    && distance <= params.influence_radius
+                {
+                    let offset = *point - tree.cross_sections[node].center;
+                    pull[node] += offset.normalize_or_zero();
+                    attracted = true;
+                }
+            }
+
+            let mut new_nodes = Vec::new();
+            if attracted {
+                colonizing = true;
+                for (node, pull) in pull.iter().enumerate() {
+                    if *pull == Vec3::ZERO {
+                        continue;
+                    }
+                    // A single iteration can add a node for every tip, so the
+                    // budget is checked per node as well
+                    if tree.cross_sections.len() >= budget {
+                        tree.budget_exhausted = true;
+                        break;
+                    }
+                    let direction = pull.try_normalize().unwrap_or(directions[node]);
+                    let center = tree.cross_sections[node].center + direction * step;
+
+                    // Opposing points can pull a tip back onto an existing child
+                    let duplicate = tree.cross_sections[node]
+                        .children_indices
+                        .iter()
+                        .any(|&child| tree.cross_sections[child].center.distance(center) < step * 0.25);
+                    if !duplicate {
+                        new_nodes.push(Self::push_node(tree, node, center, direction));
+                        directions.push(direction);
+                    }
+                }
+            } else if !colonizing && tree.cross_sections[leader].center.y < crown_top {
+                // Grow the trunk straight toward the crown until points take over
+                let direction = directions[leader];
+                let center = tree.cross_sections[leader].center + direction * step;
+                leader = Self::push_node(tree, leader, center, direction);
+                directions.push(direction);
+                new_nodes.push(leader);
+            }
+
+            if new_nodes.is_empty() {
+                break;
+            }
+
+            for (point, nearest) in points.iter().zip(closest.iter_mut()) {
+                let Some((mut node, mut distance)) = *nearest else { continue };
+                for &candidate in &new_nodes {
+                    let candidate_distance = point.distance(tree.cross_sections[candidate].center);
+                    if candidate_distance < distance {
+                        node = candidate;
+                        distance = candidate_distance;
+                    }
+                }
+                *nearest = (distance > params.kill_radius).then_some((node, distance));
+            }
+        }
+
+        Self::finish_cross_sections(tree, first_grown, context.params.branching.pipe_exponent);
+    }
+}
+
+impl BranchGenerator for SpaceColonizationSystem {
+    /// The attraction points are used up once the crown has grown, so a
+    /// grafted limb is a random walk limb.
+    fn graft(&self, tree: &mut TreeStructure, parent: usize, direction: Vec3, context: &GenerationContext, rng: &mut SmallRng) {
+        BranchingSystem::new().graft(tree, parent, direction, context, rng);
+    }
+}
+
+impl Default for SpaceColonizationSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{BranchingModel, TreeParameters, generator::ModularTreeGenerator};
+
+    #[test]
+    fn growth_stops_at_the_budget() {
+        let mut params = TreeParameters::default();
+        params.branching.model = BranchingModel::SpaceColonization;
+        params.roots.enable = false;
+        params.general.max_cross_sections = 100;
+
+        let tree = ModularTreeGenerator::new().generate_tree(&params);
+        assert!(tree.budget_exhausted);
+        assert_eq!(tree.cross_sections.len(), 100);
+    }
+}
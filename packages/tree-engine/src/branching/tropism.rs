@@ -0,0 +1,57 @@
+use crate::core::{branch_order, GeneralParams, TropismParams};
+use crate::modifiers::bend::rotate_toward;
+use glam::{Quat, Vec3};
+
+/// Largest turn in radians a single segment makes toward a stimulus, reached
+/// at full strength when growing perpendicular to it
+const MAX_TURN_PER_SEGMENT: f32 = 0.08;
+
+/// Strength at `depth`, interpolated over the branch order from `strength`
+/// on the trunk to `strength * depth_scale` at `general.max_depth`.
+pub fn depth_strength(strength: f32, depth_scale: f32, depth: u32, general: &GeneralParams) -> f32 {
+    strength * (1.0 + (depth_scale - 1.0) * branch_order(depth, general))
+}
+
+/// Rotation steering one segment growing along `direction` at `depth`:
+/// phototropism turns it toward the light, then gravitropism toward or away
+/// from the ground. Like the turtle tropism of classic L-systems, each turn
+/// is proportional to how far the segment points away from the stimulus, so
+/// growth bends smoothly and settles once aligned.
+pub fn tropism_rotation(direction: Vec3, depth: u32, general: &GeneralParams, params: &TropismParams) -> Quat {
+    let mut rotation = Quat::IDENTITY;
+
+    if params.phototropism > 0.0
+        && let Some(light) = params.light_direction.try_normalize()
+    {
+        let strength = depth_strength(params.phototropism, params.phototropism_depth_scale, depth, general);
+        rotation = turn_toward(direction, light, strength);
+    }
+
+    if params.gravitropism != 0.0 {
+        let direction = rotation * direction;
+        let strength = depth_strength(params.gravitropism, params.gravitropism_depth_scale, depth, general);
+        let target = if strength > 0.0 { Vec3::NEG_Y } else { Vec3::Y };
+        rotation = turn_toward(direction, target, strength.abs()) * rotation;
+    }
+
+    rotation
+}
+
+fn turn_toward(direction: Vec3, target: Vec3, strength: f32) -> Quat {
+    let angle = strength * MAX_TURN_PER_SEGMENT * direction.cross(target).length();
+    rotate_toward(direction, target, angle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strength_follows_the_branch_order() {
+        let general = GeneralParams { max_depth: 4, ..GeneralParams::default() };
+        assert_eq!(depth_strength(0.5, 3.0, 0, &general), 0.5);
+        assert_eq!(depth_strength(0.5, 3.0, 2, &general), 1.0);
+        assert_eq!(depth_strength(0.5, 3.0, 4, &general), 1.5);
+        assert_eq!(depth_strength(0.5, 3.0, 9, &general), 1.5);
+    }
+}
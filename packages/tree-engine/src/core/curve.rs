@@ -0,0 +1,71 @@
+use crate::core::GeneralParams;
+use serde::{Deserialize, Serialize};
+
+/// Piecewise-linear curve through `[x, y]` control points sorted by `x`.
+/// Outside the first and last points the curve stays flat; a curve without
+/// points is 1 everywhere. Serialized as a list of pairs, `[[0, 1], [1, 0.5]]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Curve(pub Vec<[f32; 2]>);
+
+impl Curve {
+    pub fn new(points: &[[f32; 2]]) -> Self {
+        Curve(points.to_vec())
+    }
+
+    /// Curve with the same value everywhere.
+    pub fn constant(value: f32) -> Self {
+        Curve(vec![[0.0, value], [1.0, value]])
+    }
+
+    /// Curve over `level / levels` that holds each value from its starting
+    /// level up to the level before the next step, then ramps to the next
+    /// value. `steps` are `(starting level, value)` pairs in increasing order.
+    pub fn steps(levels: u32, steps: &[(u32, f32)]) -> Self {
+        let levels = levels.max(1) as f32;
+        let mut points = Vec::with_capacity(steps.len() * 2);
+        for (index, &(start, value)) in steps.iter().enumerate() {
+            points.push([start as f32 / levels, value]);
+            if let Some(&(next, _)) = steps.get(index + 1)
+                && next > start + 1
+            {
+                points.push([(next - 1) as f32 / levels, value]);
+            }
+        }
+        Curve(points)
+    }
+
+    /// Value of the curve at `x`.
+    pub fn evaluate(&self, x: f32) -> f32 {
+        let points = &self.0;
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return 1.0;
+        };
+        if x <= first[0] {
+            return first[1];
+        }
+        if x >= last[0] {
+            return last[1];
+        }
+
+        let next = points.iter().position(|point| point[0] > x).unwrap_or(points.len() - 1);
+        let [x0, y0] = points[next - 1];
+        let [x1, y1] = points[next];
+        let span = x1 - x0;
+        if span <= 0.0 {
+            return y1;
+        }
+        y0 + (y1 - y0) * (x - x0) / span
+    }
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve::constant(1.0)
+    }
+}
+
+/// Position of `depth` between the trunk (0) and `max_depth` (1), the
+/// x-axis of the depth curves.
+pub fn branch_order(depth: u32, general_params: &GeneralParams) -> f32 {
+    (depth as f32 / general_params.max_depth.max(1) as f32).min(1.0)
+}
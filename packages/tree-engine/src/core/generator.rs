@@ -1,55 +1,283 @@
-use crate::core::{TreeParameters, GenerationContext, TreeSubsystem};
+use crate::core::{
+    TreeParameters, GenerationContext, ParameterDiff, RngStream,
+    TrunkGenerator, BranchGenerator, RootGenerator, TwigGenerator,
+};
 use crate::structure::TreeStructure;
 use crate::trunk::TrunkSystem;
 use crate::roots::RootSystem;
 use crate::twigs::TwigSystem;
 use crate::branching::BranchingSystem;
+use crate::modifiers::{self, TreeModifier};
+use crate::editing;
+use std::collections::BTreeMap;
 
+/// Pipeline stages in execution order. Regenerating from a stage reruns that
+/// stage and every stage after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GenerationStage {
+    Trunk,
+    Branching,
+    Roots,
+    Edits,
+    Twigs,
+    Modifiers,
+}
+
+impl GenerationStage {
+    pub const ALL: [GenerationStage; 6] = [
+        GenerationStage::Trunk,
+        GenerationStage::Branching,
+        GenerationStage::Roots,
+        GenerationStage::Edits,
+        GenerationStage::Twigs,
+        GenerationStage::Modifiers,
+    ];
+
+    /// First stage that reads the given parameter group (a top-level field of
+    /// `TreeParameters`). Unknown groups conservatively rerun everything.
+    pub fn for_group(group: &str) -> GenerationStage {
+        match group {
+            "branching" | "space_colonization" | "l_system" | "growth" | "light" | "tropism" | "envelope" | "guides" => GenerationStage::Branching,
+            "roots" => GenerationStage::Roots,
+            "edits" => GenerationStage::Edits,
+            "twigs" => GenerationStage::Twigs,
+            "modifiers" => GenerationStage::Modifiers,
+            _ => GenerationStage::Trunk,
+        }
+    }
+
+    /// Earliest stage affected by the differences between two parameter sets,
+    /// or `None` if nothing changed.
+    pub fn first_affected(old: &TreeParameters, new: &TreeParameters) -> Option<GenerationStage> {
+        ParameterDiff::new(old, new).first_affected()
+    }
+}
+
+/// Intermediate trees kept between regenerations: the input each stage saw the
+/// last time it ran, so later stages can be rerun without redoing earlier ones.
+#[derive(Default)]
+pub struct GenerationCache {
+    stage_inputs: BTreeMap<GenerationStage, TreeStructure>,
+}
+
+impl GenerationCache {
+    pub fn clear(&mut self) {
+        self.stage_inputs.clear();
+    }
+
+    /// Tree `stage` started from the last time it ran, if it has run since
+    /// the cache was cleared.
+    pub fn stage_input(&self, stage: GenerationStage) -> Option<&TreeStructure> {
+        self.stage_inputs.get(&stage)
+    }
+}
+
+/// Runs the generation pipeline. Each stage is a boxed subsystem so custom
+/// implementations can be swapped in with `ModularTreeGenerator::builder()`.
 pub struct ModularTreeGenerator {
-    trunk_system: TrunkSystem,
-    branching_system: BranchingSystem,
-    root_system: RootSystem,
-    twig_system: TwigSystem,
+    trunk_system: Box<dyn TrunkGenerator>,
+    branching_system: Box<dyn BranchGenerator>,
+    root_system: Box<dyn RootGenerator>,
+    twig_system: Box<dyn TwigGenerator>,
+    custom_modifiers: Vec<Box<dyn TreeModifier>>, // Run after the modifier stack from the parameters
 }
 
 impl ModularTreeGenerator {
+    /// Generator using the built-in subsystems.
     pub fn new() -> Self {
-        ModularTreeGenerator {
-            trunk_system: TrunkSystem::new(),
-            branching_system: BranchingSystem::new(),
-            root_system: RootSystem::new(),
-            twig_system: TwigSystem::new(),
-        }
+        Self::builder().build()
+    }
+
+    pub fn builder() -> ModularTreeGeneratorBuilder {
+        ModularTreeGeneratorBuilder::default()
     }
 
     pub fn generate_tree(&self, params: &TreeParameters) -> TreeStructure {
-        let mut tree = TreeStructure::new();
-        let mut context = GenerationContext::new(&params.general);
-        
-        // Step 1: Generate trunk base
-        TrunkSystem::generate(&params.trunk, &mut tree, &mut context.rng);
-        
-        // Step 2: Generate branching structure (complex recursive process)
-        self.branching_system.generate_branches(
-            &params.branching,
-            &params.trunk,
-            &params.general,
-            &mut tree, 
-            &mut context.rng
-        );
-        
-        // Step 3: Generate root system
-        RootSystem::generate(&params.roots, &mut tree, &mut context.rng);
-        
-        // Step 4: Generate twigs
-        TwigSystem::generate(&params.twigs, &mut tree, &mut context.rng);
-        
+        self.regenerate(params, GenerationStage::Trunk, &mut GenerationCache::default())
+    }
+
+    /// Rerun the pipeline from `from` onwards, starting from the cached input of
+    /// that stage. Falls back to a full generation when nothing is cached.
+    pub fn regenerate(
+        &self,
+        params: &TreeParameters,
+        from: GenerationStage,
+        cache: &mut GenerationCache,
+    ) -> TreeStructure {
+        let (start, mut tree) = match cache.stage_inputs.get(&from) {
+            Some(input) if from > GenerationStage::Trunk => (from, input.clone()),
+            _ => (GenerationStage::Trunk, TreeStructure::new()),
+        };
+        let context = GenerationContext::new(params);
+
+        for stage in GenerationStage::ALL.into_iter().filter(|stage| *stage >= start) {
+            if stage > start {
+                cache.stage_inputs.insert(stage, tree.clone());
+            }
+            self.run_stage(stage, params, &context, &mut tree);
+        }
+
+        tree.build_hierarchy();
         tree
     }
+
+    fn run_stage(
+        &self,
+        stage: GenerationStage,
+        params: &TreeParameters,
+        context: &GenerationContext,
+        tree: &mut TreeStructure,
+    ) {
+        match stage {
+            // Step 1: Generate trunk base
+            GenerationStage::Trunk => {
+                let mut rng = context.rng(RngStream::Trunk);
+                self.trunk_system.generate(&params.trunk, context, tree, &mut rng);
+            }
+            // Step 2: Generate branching structure (complex recursive process)
+            GenerationStage::Branching => {
+                let mut rng = context.rng(RngStream::Branching);
+                self.branching_system.generate(&params.branching, context, tree, &mut rng);
+            }
+            // Step 3: Generate root system
+            GenerationStage::Roots => {
+                let mut rng = context.rng(RngStream::Roots);
+                self.root_system.generate(&params.roots, context, tree, &mut rng);
+            }
+            // Step 4: Replay manual skeleton edits
+            GenerationStage::Edits => {
+                let mut rng = context.rng(RngStream::Edits);
+                editing::apply_edits(&params.edits, self.branching_system.as_ref(), context, tree, &mut rng);
+            }
+            // Step 5: Generate twigs
+            GenerationStage::Twigs => {
+                let mut rng = context.rng(RngStream::Twigs);
+                self.twig_system.generate(&params.twigs, context, tree, &mut rng);
+            }
+            // Step 6: Post-process with the modifier stack
+            GenerationStage::Modifiers => {
+                let mut rng = context.rng(RngStream::Modifiers);
+                modifiers::apply_modifiers(&params.modifiers, tree, &mut rng);
+                for modifier in &self.custom_modifiers {
+                    modifier.apply(tree, &mut rng);
+                }
+            }
+        }
+    }
 }
 
 impl Default for ModularTreeGenerator {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// Builder for a `ModularTreeGenerator` with custom subsystems. Stages that are
+/// not set use the built-in implementation.
+///
+/// ```ignore
+/// let generator = ModularTreeGenerator::builder()
+///     .branching(MyBranchingSystem::new())
+///     .build();
+/// ```
+#[derive(Default)]
+pub struct ModularTreeGeneratorBuilder {
+    trunk_system: Option<Box<dyn TrunkGenerator>>,
+    branching_system: Option<Box<dyn BranchGenerator>>,
+    root_system: Option<Box<dyn RootGenerator>>,
+    twig_system: Option<Box<dyn TwigGenerator>>,
+    custom_modifiers: Vec<Box<dyn TreeModifier>>,
+}
+
+impl ModularTreeGeneratorBuilder {
+    pub fn trunk(mut self, system: impl TrunkGenerator + 'static) -> Self {
+        self.trunk_system = Some(Box::new(system));
+        self
+    }
+
+    pub fn branching(mut self, system: impl BranchGenerator + 'static) -> Self {
+        self.branching_system = Some(Box::new(system));
+        self
+    }
+
+    pub fn roots(mut self, system: impl RootGenerator + 'static) -> Self {
+        self.root_system = Some(Box::new(system));
+        self
+    }
+
+    pub fn twigs(mut self, system: impl TwigGenerator + 'static) -> Self {
+        self.twig_system = Some(Box::new(system));
+        self
+    }
+
+    /// Append a modifier that always runs after the parameter-driven modifier stack.
+    pub fn modifier(mut self, modifier: impl TreeModifier + 'static) -> Self {
+        self.custom_modifiers.push(Box::new(modifier));
+        self
+    }
+
+    pub fn build(self) -> ModularTreeGenerator {
+        ModularTreeGenerator {
+            trunk_system: self.trunk_system.unwrap_or_else(|| Box::new(TrunkSystem::new())),
+            branching_system: self.branching_system.unwrap_or_else(|| Box::new(BranchingSystem::new())),
+            root_system: self.root_system.unwrap_or_else(|| Box::new(RootSystem::new())),
+            twig_system: self.twig_system.unwrap_or_else(|| Box::new(TwigSystem::new())),
+            custom_modifiers: self.custom_modifiers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_map_to_the_first_stage_reading_them() {
+        assert_eq!(GenerationStage::for_group("general"), GenerationStage::Trunk);
+        assert_eq!(GenerationStage::for_group("trunk"), GenerationStage::Trunk);
+        for group in ["branching", "space_colonization", "l_system", "growth", "light", "tropism", "envelope", "guides"] {
+            assert_eq!(GenerationStage::for_group(group), GenerationStage::Branching, "{}", group);
+        }
+        assert_eq!(GenerationStage::for_group("roots"), GenerationStage::Roots);
+        assert_eq!(GenerationStage::for_group("edits"), GenerationStage::Edits);
+        assert_eq!(GenerationStage::for_group("twigs"), GenerationStage::Twigs);
+        assert_eq!(GenerationStage::for_group("modifiers"), GenerationStage::Modifiers);
+        assert_eq!(GenerationStage::for_group("unknown"), GenerationStage::Trunk);
+    }
+
+    #[test]
+    fn first_affected_is_the_earliest_changed_stage() {
+        let old = TreeParameters::default();
+        assert_eq!(GenerationStage::first_affected(&old, &old.clone()), None);
+
+        let mut new = old.clone();
+        new.twigs.scale = 2.0;
+        assert_eq!(GenerationStage::first_affected(&old, &new), Some(GenerationStage::Twigs));
+
+        new.roots.depth = 2.0;
+        assert_eq!(GenerationStage::first_affected(&old, &new), Some(GenerationStage::Roots));
+
+        new.general.seed += 1;
+        assert_eq!(GenerationStage::first_affected(&old, &new), Some(GenerationStage::Trunk));
+    }
+
+    #[test]
+    fn partial_regeneration_matches_a_full_one() {
+        let generator = ModularTreeGenerator::new();
+        let mut cache = GenerationCache::default();
+        let old = TreeParameters::default();
+        generator.regenerate(&old, GenerationStage::Trunk, &mut cache);
+
+        let mut new = old.clone();
+        new.roots.density = 3;
+        let stage = GenerationStage::first_affected(&old, &new).unwrap();
+        let partial = generator.regenerate(&new, stage, &mut cache);
+        let full = generator.generate_tree(&new);
+
+        assert_eq!(partial.cross_sections.len(), full.cross_sections.len());
+        assert_eq!(partial.twigs.len(), full.twigs.len());
+        for (a, b) in partial.cross_sections.iter().zip(&full.cross_sections) {
+            assert_eq!(a.center, b.center);
+        }
+    }
+}
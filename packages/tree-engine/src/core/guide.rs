@@ -0,0 +1,109 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Spline spans are sampled this many times when turned into a polyline
+const SPLINE_SAMPLES_PER_SPAN: usize = 12;
+
+/// A path drawn by the user for a trunk or limb to follow, in tree space.
+/// Serialized as `{"points": [[0, 0, 0], [0, 2, 0.5]], "interpolation": "spline"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GuideCurve {
+    pub points: Vec<Vec3>,
+    #[serde(default)]
+    pub interpolation: GuideInterpolation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuideInterpolation {
+    #[default]
+    Polyline,   // Straight lines between the points
+    Spline,     // Smooth Catmull-Rom curve through every point
+}
+
+impl GuideInterpolation {
+    pub const NAMES: &'static [&'static str] = &["polyline", "spline"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GuideInterpolation::Polyline => "polyline",
+            GuideInterpolation::Spline => "spline",
+        }
+    }
+}
+
+impl GuideCurve {
+    pub fn new(points: Vec<Vec3>, interpolation: GuideInterpolation) -> Self {
+        GuideCurve { points, interpolation }
+    }
+
+    /// The curve as a polyline: the points themselves, or dense samples of
+    /// the spline through them.
+    pub fn polyline(&self) -> Vec<Vec3> {
+        if self.interpolation == GuideInterpolation::Polyline || self.points.len() < 3 {
+            return self.points.clone();
+        }
+
+        let points = &self.points;
+        let last = points.len() - 1;
+        let mut samples = Vec::with_capacity(last * SPLINE_SAMPLES_PER_SPAN + 1);
+        for span in 0..last {
+            // The end points are mirrored so the curve starts and ends on them
+            let p0 = if span == 0 { 2.0 * points[0] - points[1] } else { points[span - 1] };
+            let (p1, p2) = (points[span], points[span + 1]);
+            let p3 = if span + 1 == last { 2.0 * points[last] - points[last - 1] } else { points[span + 2] };
+            for sample in 0..SPLINE_SAMPLES_PER_SPAN {
+                let t = sample as f32 / SPLINE_SAMPLES_PER_SPAN as f32;
+                samples.push(catmull_rom(p0, p1, p2, p3, t));
+            }
+        }
+        samples.push(points[last]);
+        samples
+    }
+}
+
+/// Uniform Catmull-Rom interpolation between `p1` and `p2`.
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spline_passes_through_every_point() {
+        let points = vec![Vec3::ZERO, Vec3::new(0.0, 2.0, 0.0), Vec3::new(1.0, 3.0, 1.0), Vec3::new(3.0, 3.0, 0.0)];
+        let samples = GuideCurve::new(points.clone(), GuideInterpolation::Spline).polyline();
+
+        assert_eq!(samples.len(), 3 * SPLINE_SAMPLES_PER_SPAN + 1);
+        for (index, point) in points.iter().enumerate() {
+            assert!(samples[index * SPLINE_SAMPLES_PER_SPAN].distance(*point) < 1e-5);
+        }
+    }
+
+    #[test]
+    fn mirrored_end_points_keep_a_straight_guide_straight() {
+        // With the end points mirrored, evenly spaced points on a line give
+        // evenly spaced samples right up to both ends
+        let points = vec![Vec3::ZERO, Vec3::Y, Vec3::Y * 2.0];
+        let samples = GuideCurve::new(points, GuideInterpolation::Spline).polyline();
+        for (index, sample) in samples.iter().enumerate() {
+            let expected = Vec3::Y * index as f32 / SPLINE_SAMPLES_PER_SPAN as f32;
+            assert!(sample.distance(expected) < 1e-5, "sample {} is {} instead of {}", index, sample, expected);
+        }
+    }
+
+    #[test]
+    fn polyline_guides_and_short_splines_keep_their_points() {
+        let points = vec![Vec3::ZERO, Vec3::new(1.0, 2.0, 0.0), Vec3::new(0.0, 4.0, 1.0)];
+        assert_eq!(GuideCurve::new(points.clone(), GuideInterpolation::Polyline).polyline(), points);
+        assert_eq!(GuideCurve::new(points[..2].to_vec(), GuideInterpolation::Spline).polyline(), points[..2]);
+    }
+}
@@ -0,0 +1,269 @@
+use crate::core::TreeParameters;
+use crate::core::generator::GenerationStage;
+
+/// Oldest steps are dropped once the history holds this many snapshots
+const HISTORY_LIMIT: usize = 200;
+
+/// What a history step changed, used to decide whether the next change can
+/// be folded into it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    Fields(Vec<String>), // Only these scalar parameters, by dotted name
+    Structural,          // Lists or optional values changed, e.g. a new edit; never merged
+    Batch,               // Everything between begin_update and commit_update
+}
+
+impl ChangeKind {
+    /// Classify the difference between two parameter sets.
+    pub fn between(old: &TreeParameters, new: &TreeParameters) -> ChangeKind {
+        ParameterDiff::new(old, new).change()
+    }
+}
+
+/// Two parameter sets serialized once, so recording a change in the history
+/// and finding the pipeline stages it invalidates share the work.
+pub struct ParameterDiff {
+    values: Option<(serde_json::Value, serde_json::Value)>, // None if either failed to serialize
+}
+
+impl ParameterDiff {
+    pub fn new(old: &TreeParameters, new: &TreeParameters) -> Self {
+        ParameterDiff { values: serde_json::to_value(old).ok().zip(serde_json::to_value(new).ok()) }
+    }
+
+    /// What changed, as recorded in the history.
+    pub fn change(&self) -> ChangeKind {
+        let Some((old, new)) = &self.values else {
+            return ChangeKind::Structural;
+        };
+        let mut fields = Vec::new();
+        if collect_changed_fields(old, new, "", &mut fields) {
+            ChangeKind::Fields(fields)
+        } else {
+            ChangeKind::Structural
+        }
+    }
+
+    /// Earliest pipeline stage reading a changed parameter group, or `None`
+    /// if nothing changed.
+    pub fn first_affected(&self) -> Option<GenerationStage> {
+        let Some((serde_json::Value::Object(old), serde_json::Value::Object(new))) = &self.values else {
+            return Some(GenerationStage::Trunk);
+        };
+        old.iter()
+            .filter(|(group, value)| new.get(group.as_str()) != Some(*value))
+            .map(|(group, _)| GenerationStage::for_group(group))
+            .min()
+    }
+}
+
+/// Push the dotted names of the scalars that differ between `old` and `new`.
+/// Returns false if anything other than a scalar changed.
+fn collect_changed_fields(old: &serde_json::Value, new: &serde_json::Value, path: &str, fields: &mut Vec<String>) -> bool {
+    use serde_json::Value;
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            if old.len() != new.len() {
+                return false;
+            }
+            old.iter().all(|(key, old_value)| {
+                let field = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                new.get(key).is_some_and(|new_value| collect_changed_fields(old_value, new_value, &field, fields))
+            })
+        }
+        _ if old == new => true,
+        (Value::Number(_), Value::Number(_)) | (Value::Bool(_), Value::Bool(_)) | (Value::String(_), Value::String(_)) => {
+            fields.push(path.to_string());
+            true
+        }
+        _ => false,
+    }
+}
+
+struct HistoryStep {
+    params: TreeParameters,
+    change: ChangeKind,
+}
+
+/// Undo/redo history of parameter sets, skeleton edits included. Every step
+/// is a full snapshot, so any earlier state is restored directly without
+/// passing through the ones in between.
+///
+/// A change to the same scalar parameters as the step before replaces that
+/// step instead of adding one, so dragging a slider leaves a single step.
+/// Nothing ends a step on its own: `seal` must be called when the slider is
+/// released, or the next drag of it is merged into the same step.
+pub struct ParameterHistory {
+    steps: Vec<HistoryStep>,
+    current: usize,
+    sealed: bool, // The current step takes no more merged changes
+}
+
+impl ParameterHistory {
+    pub fn new(initial: TreeParameters) -> Self {
+        ParameterHistory {
+            steps: vec![HistoryStep { params: initial, change: ChangeKind::Structural }],
+            current: 0,
+            sealed: true,
+        }
+    }
+
+    /// Record `params` as the newest state, dropping any redo steps.
+    pub fn record(&mut self, params: TreeParameters, change: ChangeKind) {
+        self.steps.truncate(self.current + 1);
+
+        let merge = !self.sealed && change != ChangeKind::Structural && self.steps[self.current].change == change;
+        if merge {
+            self.steps[self.current].params = params;
+            return;
+        }
+
+        self.steps.push(HistoryStep { params, change });
+        if self.steps.len() > HISTORY_LIMIT {
+            self.steps.remove(0);
+        }
+        self.current = self.steps.len() - 1;
+        self.sealed = false;
+    }
+
+    /// Close the current step so the next change starts a new one.
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.current > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.current + 1 < self.steps.len()
+    }
+
+    /// Step back, returning the parameters to restore.
+    pub fn undo(&mut self) -> Option<&TreeParameters> {
+        let target = self.current.checked_sub(1)?;
+        self.jump_to(target)
+    }
+
+    /// Step forward again after an undo.
+    pub fn redo(&mut self) -> Option<&TreeParameters> {
+        self.jump_to(self.current + 1)
+    }
+
+    /// Go straight to step `index` (0 is the oldest kept state).
+    pub fn jump_to(&mut self, index: usize) -> Option<&TreeParameters> {
+        let step = self.steps.get(index)?;
+        self.current = index;
+        self.sealed = true;
+        Some(&step.params)
+    }
+
+    /// Number of steps kept, including the current one.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Index of the current step.
+    pub fn position(&self) -> usize {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::SkeletonEdit;
+
+    fn with_height(height: f32) -> TreeParameters {
+        let mut params = TreeParameters::default();
+        params.trunk.height = height;
+        params
+    }
+
+    fn height_change() -> ChangeKind {
+        ChangeKind::Fields(vec!["trunk.height".to_string()])
+    }
+
+    #[test]
+    fn changes_name_the_scalars_that_differ() {
+        let old = TreeParameters::default();
+        assert_eq!(ChangeKind::between(&old, &old.clone()), ChangeKind::Fields(Vec::new()));
+        assert_eq!(ChangeKind::between(&old, &with_height(old.trunk.height + 1.0)), height_change());
+
+        let mut new = old.clone();
+        new.edits.push(SkeletonEdit::Prune { branch: 1, distance: 0.0 });
+        assert_eq!(ChangeKind::between(&old, &new), ChangeKind::Structural);
+    }
+
+    #[test]
+    fn repeated_changes_merge_until_sealed() {
+        let mut history = ParameterHistory::new(with_height(1.0));
+        history.record(with_height(2.0), height_change());
+        history.record(with_height(3.0), height_change());
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.position(), 1);
+
+        history.seal();
+        history.record(with_height(4.0), height_change());
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.undo().unwrap().trunk.height, 3.0);
+    }
+
+    #[test]
+    fn different_and_structural_changes_are_never_merged() {
+        let mut history = ParameterHistory::new(with_height(1.0));
+        history.record(with_height(2.0), height_change());
+        history.record(with_height(2.0), ChangeKind::Fields(vec!["trunk.size".to_string()]));
+        history.record(with_height(2.0), ChangeKind::Structural);
+        history.record(with_height(2.0), ChangeKind::Structural);
+        assert_eq!(history.len(), 5);
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_steps() {
+        let mut history = ParameterHistory::new(with_height(1.0));
+        assert!(!history.can_undo());
+        history.record(with_height(2.0), height_change());
+        history.seal();
+        history.record(with_height(3.0), height_change());
+
+        assert_eq!(history.undo().unwrap().trunk.height, 2.0);
+        assert_eq!(history.undo().unwrap().trunk.height, 1.0);
+        assert!(history.undo().is_none());
+        assert!(history.can_redo());
+        assert_eq!(history.redo().unwrap().trunk.height, 2.0);
+        assert_eq!(history.redo().unwrap().trunk.height, 3.0);
+        assert!(history.redo().is_none());
+        assert_eq!(history.position(), 2);
+    }
+
+    #[test]
+    fn recording_after_undo_drops_the_redo_steps() {
+        let mut history = ParameterHistory::new(with_height(1.0));
+        history.record(with_height(2.0), height_change());
+        history.seal();
+        history.record(with_height(3.0), height_change());
+        history.undo();
+
+        // Undoing seals the step, so this change is a step of its own
+        history.record(with_height(5.0), height_change());
+        assert_eq!(history.len(), 3);
+        assert!(!history.can_redo());
+        assert_eq!(history.undo().unwrap().trunk.height, 2.0);
+    }
+
+    #[test]
+    fn the_oldest_steps_are_dropped_past_the_limit() {
+        let mut history = ParameterHistory::new(with_height(0.0));
+        for step in 1..=HISTORY_LIMIT + 10 {
+            history.record(with_height(step as f32), ChangeKind::Structural);
+        }
+        assert_eq!(history.len(), HISTORY_LIMIT);
+        assert_eq!(history.jump_to(0).unwrap().trunk.height, 11.0);
+    }
+}
@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One symbol of an L-system string, optionally carrying a numeric argument
+/// such as the `30` in `+(30)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Module {
+    pub symbol: char,
+    pub argument: Option<f32>,
+}
+
+/// A parsed L-system string. It is exchanged as plain text (`F[+(30)A]!A`) in
+/// JSON, so malformed strings are rejected when the parameters are loaded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Modules(pub Vec<Module>);
+
+impl Modules {
+    /// Parse a module string. Whitespace is ignored; any other character is a
+    /// symbol and may be followed by a parenthesised number.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut modules = Vec::new();
+        let mut chars = text.chars().filter(|c| !c.is_whitespace()).peekable();
+
+        while let Some(symbol) = chars.next() {
+            if symbol == '(' || symbol == ')' {
+                return Err(format!("unexpected '{}' in \"{}\"", symbol, text));
+            }
+
+            let mut argument = None;
+            if chars.peek() == Some(&'(') {
+                chars.next();
+                let mut number = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ')' {
+                        closed = true;
+                        break;
+                    }
+                    number.push(c);
+                }
+                if !closed {
+                    return Err(format!("missing ')' after '{}' in \"{}\"", symbol, text));
+                }
+                let value = number
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| format!("invalid argument \"{}\" for '{}' in \"{}\"", number, symbol, text))?;
+                argument = Some(value);
+            }
+
+            modules.push(Module { symbol, argument });
+        }
+
+        Ok(Modules(modules))
+    }
+}
+
+impl TryFrom<String> for Modules {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        Modules::parse(&text)
+    }
+}
+
+impl From<Modules> for String {
+    fn from(modules: Modules) -> Self {
+        modules.to_string()
+    }
+}
+
+impl fmt::Display for Modules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for module in &self.0 {
+            write!(f, "{}", module.symbol)?;
+            if let Some(argument) = module.argument {
+                write!(f, "({})", argument)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Production rule `symbol -> successor`. Rules sharing a symbol are chosen
+/// between at random, weighted by their probabilities.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LSystemRule {
+    pub symbol: char,
+    pub successor: Modules,
+    #[serde(default = "certain")]
+    pub probability: f32,
+}
+
+fn certain() -> f32 {
+    1.0
+}
+
+/// Grammar and turtle settings for the L-system branching model.
+///
+/// Turtle commands: `F` draws a segment, `+`/`-` yaw, `&`/`^` pitch, `\`/`/` roll, `|` turns around, `[`/`]` start and end
+/// a branch, `!` scales the radius and `"` scales the segment length. Angle,
+/// length and scale commands use their argument when given, the settings
+/// below otherwise. Any other symbol is only used by the rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LSystemParams {
+    pub axiom: Modules,
+    pub rules: Vec<LSystemRule>,
+    pub iterations: u32,     // Number of rewriting passes
+    pub angle: f32,          // Default turn angle in degrees
+    pub length: f32,         // Default length of an `F` segment
+    pub radius_scale: f32,   // Default factor applied by `!`
+    pub length_scale: f32,   // Default factor applied by `"`
+}
+
+impl Default for LSystemParams {
+    fn default() -> Self {
+        let rule = |successor: &str, probability| LSystemRule {
+            symbol: 'A',
+            successor: Modules::parse(successor).expect("default rule is valid"),
+            probability,
+        };
+        LSystemParams {
+            axiom: Modules::parse("FFFFFA").expect("default axiom is valid"),
+            rules: vec![
+                rule("!\"[&FA]/////[&FA]///////[&FA]", 0.8),
+                rule("!\"F[&FA]//////[&FA]", 0.2),
+            ],
+            iterations: 6,
+            angle: 25.0,
+            length: 1.0,
+            radius_scale: 0.6,
+            length_scale: 0.85,
+        }
+    }
+}
+
+/// Error from `LSystemParams::from_text`, pointing at the offending line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrammarError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "L-system line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for GrammarError {}
+
+impl LSystemParams {
+    /// Load a grammar from its text form:
+    ///
+    /// ```text
+    /// # Comments start with '#'
+    /// axiom: FFFFFA
+    /// iterations: 6
+    /// angle: 25
+    /// A -> !"[&FA]/////[&FA]///////[&FA] : 0.8
+    /// A -> !"F[&FA]//////[&FA] : 0.2
+    /// ```
+    ///
+    /// Settings that are not given keep their defaults. The rules in the text
+    /// replace the default rules; a rule without `: probability` has weight 1.
+    pub fn from_text(text: &str) -> Result<Self, GrammarError> {
+        let mut params = LSystemParams { rules: Vec::new(), ..LSystemParams::default() };
+
+        for (index, line) in text.lines().enumerate() {
+            let error = |message: String| GrammarError { line: index + 1, message };
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some((symbol, rest)) = line.split_once("->") {
+                let mut symbols = symbol.trim().chars();
+                let symbol = match (symbols.next(), symbols.next()) {
+                    (Some(symbol), None) => symbol,
+                    _ => return Err(error(format!("rule must rewrite a single symbol, got \"{}\"", symbol.trim()))),
+                };
+                let (successor, probability) = match rest.rsplit_once(':') {
+                    Some((successor, probability)) => {
+                        let probability = probability
+                            .trim()
+                            .parse::<f32>()
+                            .map_err(|_| error(format!("invalid probability \"{}\"", probability.trim())))?;
+                        (successor, probability)
+                    }
+                    None => (rest, 1.0),
+                };
+                let successor = Modules::parse(successor).map_err(error)?;
+                params.rules.push(LSystemRule { symbol, successor, probability });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(error(format!("expected \"key: value\" or \"symbol -> successor\", got \"{}\"", line)));
+            };
+            let value = value.trim();
+            let number = || value.parse::<f32>().map_err(|_| error(format!("invalid number \"{}\" for {}", value, key.trim())));
+            match key.trim() {
+                "axiom" => params.axiom = Modules::parse(value).map_err(error)?,
+                "iterations" => {
+                    params.iterations = value
+                        .parse()
+                        .map_err(|_| error(format!("invalid iteration count \"{}\"", value)))?
+                }
+                "angle" => params.angle = number()?,
+                "length" => params.length = number()?,
+                "radius_scale" => params.radius_scale = number()?,
+                "length_scale" => params.length_scale = number()?,
+                other => return Err(error(format!("unknown setting \"{}\"", other))),
+            }
+        }
+
+        Ok(params)
+    }
+
+    /// Write the grammar in the format read by `from_text`.
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "axiom: {}\niterations: {}\nangle: {}\nlength: {}\nradius_scale: {}\nlength_scale: {}\n",
+            self.axiom, self.iterations, self.angle, self.length, self.radius_scale, self.length_scale,
+        );
+        for rule in &self.rules {
+            text.push_str(&format!("{} -> {} : {}\n", rule.symbol, rule.successor, rule.probability));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modules_parse_symbols_and_arguments() {
+        let modules = Modules::parse("F [ +(30)A ]").unwrap();
+        let symbols: String = modules.0.iter().map(|module| module.symbol).collect();
+        assert_eq!(symbols, "F[+A]");
+        assert_eq!(modules.0[2].argument, Some(30.0));
+        assert_eq!(modules.0[3].argument, None);
+        assert_eq!(modules.to_string(), "F[+(30)A]");
+    }
+
+    #[test]
+    fn malformed_modules_are_rejected() {
+        assert!(Modules::parse("F(30").is_err());
+        assert!(Modules::parse("F)").is_err());
+        assert!(Modules::parse("(30)").is_err());
+        assert!(Modules::parse("+(abc)").is_err());
+        assert!(Modules::parse("+(inf)").is_err());
+    }
+
+    #[test]
+    fn grammar_text_round_trips() {
+        let text = "# Binary tree\naxiom: FA\niterations: 4\nangle: 30\nA -> F[+A][-A] : 0.7\nA -> FA\n";
+        let params = LSystemParams::from_text(text).unwrap();
+        assert_eq!(params.axiom, Modules::parse("FA").unwrap());
+        assert_eq!(params.iterations, 4);
+        assert_eq!(params.angle, 30.0);
+        assert_eq!(params.length, LSystemParams::default().length);
+        assert_eq!(params.rules.len(), 2);
+        assert_eq!(params.rules[0].probability, 0.7);
+        assert_eq!(params.rules[1].probability, 1.0);
+
+        let reloaded = LSystemParams::from_text(&params.to_text()).unwrap();
+        assert_eq!(reloaded.to_text(), params.to_text());
+    }
+
+    #[test]
+    fn grammar_errors_point_at_the_line() {
+        let error = LSystemParams::from_text("axiom: F\n\nAB -> F").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(LSystemParams::from_text("angle: steep").unwrap_err().line, 1);
+        assert_eq!(LSystemParams::from_text("axiom: F\ncolour: red").unwrap_err().line, 2);
+        assert_eq!(LSystemParams::from_text("A -> F : often").unwrap_err().line, 1);
+    }
+}
@@ -1,48 +1,159 @@
 pub mod parameters;
+pub mod curve;
+pub mod guide;
+pub mod history;
+pub mod lsystem;
 pub mod generator;
+pub mod presets;
+pub mod schema;
+pub mod validation;
 
 use crate::structure::TreeStructure;
+use glam::Vec3;
 use rand::rngs::SmallRng;
 
 pub use parameters::*;
+pub use curve::{branch_order, Curve};
+pub use guide::{GuideCurve, GuideInterpolation};
+pub use history::{ChangeKind, ParameterDiff, ParameterHistory};
+pub use lsystem::{GrammarError, LSystemParams, LSystemRule, Module, Modules};
+pub use presets::PRESET_NAMES;
+pub use validation::{ParamViolation, ParameterError};
 
-/// Core trait for tree generation subsystems
+/// Core trait for tree generation subsystems. Implementations are plugged into
+/// `ModularTreeGenerator` through its builder.
 pub trait TreeSubsystem {
     type Params;
     type Output;
     
     fn generate(
+        &self,
         params: &Self::Params,
+        context: &GenerationContext,
         tree: &mut TreeStructure,
         rng: &mut SmallRng,
     ) -> Self::Output;
 }
 
 /// Trait for trunk generation systems
-pub trait TrunkGenerator: TreeSubsystem<Params = TrunkParams> {}
+pub trait TrunkGenerator: TreeSubsystem<Params = TrunkParams, Output = ()> {}
 
-/// Trait for branching generation systems  
-pub trait BranchGenerator: TreeSubsystem<Params = BranchingParams> {}
+/// Trait for branching generation systems. They also grow the limbs added by
+/// graft edits.
+pub trait BranchGenerator: TreeSubsystem<Params = BranchingParams, Output = ()> {
+    /// Grow a new limb from cross-section `parent`, leaving along `direction`.
+    fn graft(&self, tree: &mut TreeStructure, parent: usize, direction: Vec3, context: &GenerationContext, rng: &mut SmallRng);
+}
 
 /// Trait for root generation systems
-pub trait RootGenerator: TreeSubsystem<Params = RootParams> {}
+pub trait RootGenerator: TreeSubsystem<Params = RootParams, Output = ()> {}
 
 /// Trait for twig generation systems
-pub trait TwigGenerator: TreeSubsystem<Params = TwigParams> {}
+pub trait TwigGenerator: TreeSubsystem<Params = TwigParams, Output = ()> {}
+
+/// Independent random streams. Each subsystem draws from its own stream so that
+/// a parameter change in one stage never reshuffles the random draws of another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngStream {
+    Trunk = 1,
+    Branching = 2,
+    Roots = 3,
+    Twigs = 4,
+    Modifiers = 5,
+    Edits = 6,
+    GuidedLimbs = 7,
+}
 
-/// Main tree generation context
-pub struct GenerationContext {
-    pub general: GeneralParams,
-    pub rng: SmallRng,
+/// Main tree generation context, shared by every stage of one generation run.
+/// Gives subsystems access to the parameters of other groups (e.g. branching
+/// reads the trunk height).
+pub struct GenerationContext<'a> {
+    pub params: &'a TreeParameters,
 }
 
-impl GenerationContext {
-    pub fn new(params: &GeneralParams) -> Self {
+impl<'a> GenerationContext<'a> {
+    pub fn new(params: &'a TreeParameters) -> Self {
+        GenerationContext { params }
+    }
+
+    /// Fresh RNG for one subsystem, seeded from the tree seed and the stream id.
+    pub fn rng(&self, stream: RngStream) -> SmallRng {
         use rand::SeedableRng;
-        
-        GenerationContext {
-            general: params.clone(),
-            rng: SmallRng::seed_from_u64(params.seed as u64),
+
+        SmallRng::seed_from_u64(sub_seed(self.params.general.seed as u64, stream as u64))
+    }
+}
+
+/// Derive an independent seed from a parent seed and a key (splitmix64 mixing),
+/// e.g. a stream id or a cross-section index.
+pub fn sub_seed(seed: u64, key: u64) -> u64 {
+    fn mix(mut z: u64) -> u64 {
+        z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    mix(seed ^ mix(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::generator::ModularTreeGenerator;
+    use crate::structure::RingType;
+    use glam::Vec3;
+
+    /// Parameters with roots, twigs and a random modifier stack, so every
+    /// stream is drawn from.
+    fn params() -> TreeParameters {
+        TreeParameters {
+            modifiers: vec![
+                ModifierEntry { enabled: true, modifier: ModifierParams::RadiusNoise(RadiusNoiseParams { amount: 0.3 }) },
+                ModifierEntry { enabled: true, modifier: ModifierParams::RandomPrune(RandomPruneParams { probability: 0.2, min_depth: 2 }) },
+            ],
+            ..TreeParameters::default()
+        }
+    }
+
+    /// Centers of the crown's cross-sections, leaving out the roots.
+    fn crown_centers(params: &TreeParameters) -> Vec<Vec3> {
+        let tree = ModularTreeGenerator::new().generate_tree(params);
+        tree.cross_sections
+            .iter()
+            .filter(|cs| !cs.component_rings.iter().any(|ring| matches!(ring.ring_type, RingType::Root { .. })))
+            .map(|cs| cs.center)
+            .collect()
+    }
+
+    #[test]
+    fn twig_and_root_density_leave_the_branches_alone() {
+        let base = params();
+        let centers = crown_centers(&base);
+
+        let mut twigs = base.clone();
+        twigs.twigs.density *= 2.0;
+        assert_eq!(crown_centers(&twigs), centers);
+
+        let mut roots = base.clone();
+        roots.roots.density += 3;
+        assert_eq!(crown_centers(&roots), centers);
+    }
+
+    #[test]
+    fn twig_scale_leaves_the_twig_positions_alone() {
+        let base = params();
+        let mut scaled = base.clone();
+        scaled.twigs.scale *= 2.0;
+
+        let generator = ModularTreeGenerator::new();
+        let a = generator.generate_tree(&base);
+        let b = generator.generate_tree(&scaled);
+        assert!(!a.twigs.is_empty());
+        assert_eq!(a.twigs.len(), b.twigs.len());
+        for (a, b) in a.twigs.iter().zip(&b.twigs) {
+            assert_eq!(a.position, b.position);
+            assert_eq!(a.scale * 2.0, b.scale);
         }
     }
-}
\ No newline at end of file
+}
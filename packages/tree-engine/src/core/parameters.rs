@@ -1,19 +1,36 @@
-#[derive(Debug, Clone)]
+use crate::core::{branch_order, Curve, GuideCurve, LSystemParams};
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct TreeParameters {
     pub general: GeneralParams,
     pub trunk: TrunkParams,
     pub branching: BranchingParams,
     pub roots: RootParams,
     pub twigs: TwigParams,
+    pub space_colonization: SpaceColonizationParams,
+    pub l_system: LSystemParams,
+    pub growth: GrowthParams,
+    pub light: LightParams,
+    pub tropism: TropismParams,
+    pub envelope: EnvelopeParams,
+    pub guides: GuideParams,
+    pub modifiers: Vec<ModifierEntry>, // Post-processing stack, applied in order
+    pub edits: Vec<SkeletonEdit>,      // Manual skeleton edits, replayed in order before twigs
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct GeneralParams {
     pub seed: u32,
     pub max_depth: u32,
+    pub max_cross_sections: u32, // Branch growth stops once the tree has this many cross-sections
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct TrunkParams {
     pub height: f32,
     pub buttressing: f32,
@@ -24,7 +41,8 @@ pub struct TrunkParams {
     pub segment_length_variation: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct BranchingParams {
     pub angle_min: f32,
     pub angle_max: f32,
@@ -35,9 +53,336 @@ pub struct BranchingParams {
     pub radius_taper: f32,
     pub azimuth_variation: f32,
     pub max_reach: f32,
+    pub model: BranchingModel,  // Growth algorithm for the branch skeleton
+    pub phyllotaxis: Phyllotaxis, // Arrangement of side branches around the parent axis
+    pub whorl_count: u32,       // Side branches per node for whorled phyllotaxis
+    pub divergence_angle: f32,  // Degrees between successive nodes for spiral phyllotaxis
+    pub codominant_probability: f32, // Chance a branching node forks into equal stems instead
+    pub codominant_count: u32,  // Stems per co-dominant fork
+    pub codominant_angle: f32,  // Degrees each co-dominant stem leans away from the parent axis
+    pub radius_model: RadiusModel, // How a fork divides its radius between the children
+    pub pipe_exponent: f32,     // Pipe model: parent r^n equals the sum of the children's r^n
+    pub lateral_importance: f32, // Pipe model: share of a side branch relative to the continuing axis
+    pub curves: DepthCurves,    // Per-level shaping of the settings above
+}
+
+/// Multipliers that shape each branching level of the random walk model.
+/// Every curve runs over normalized branch order: 0 is the trunk and 1 is
+/// `general.max_depth`. A curve left unset keeps the built-in behaviour,
+/// which depends on the absolute depth: the per-depth bend and length
+/// tables, and no scaling for the others.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DepthCurves {
+    pub angle: Option<Curve>,           // Scales the branch angle range for branches of this order
+    pub bend: Option<Curve>,            // Scales the per-segment bend range
+    pub length: Option<Curve>,          // Segments a limb may grow without forking, relative to the trunk
+    pub taper: Option<Curve>,           // Scales the radius lost per segment
+    pub frequency: Option<Curve>,       // Scales the number of segments between branching nodes
+    pub azimuth_spread: Option<Curve>,  // Scales azimuth_variation for branches of this order
 }
 
-#[derive(Debug, Clone)]
+impl DepthCurves {
+    pub const NAMES: &'static [&'static str] = &["angle", "bend", "length", "taper", "frequency", "azimuth_spread"];
+
+    /// The curve with the given field name, if it is set.
+    pub fn curve(&self, name: &str) -> Option<&Curve> {
+        match name {
+            "angle" => self.angle.as_ref(),
+            "bend" => self.bend.as_ref(),
+            "length" => self.length.as_ref(),
+            "taper" => self.taper.as_ref(),
+            "frequency" => self.frequency.as_ref(),
+            "azimuth_spread" => self.azimuth_spread.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// The field with the given name, or `None` if there is no such curve.
+    pub fn curve_mut(&mut self, name: &str) -> Option<&mut Option<Curve>> {
+        match name {
+            "angle" => Some(&mut self.angle),
+            "bend" => Some(&mut self.bend),
+            "length" => Some(&mut self.length),
+            "taper" => Some(&mut self.taper),
+            "frequency" => Some(&mut self.frequency),
+            "azimuth_spread" => Some(&mut self.azimuth_spread),
+            _ => None,
+        }
+    }
+
+    pub fn angle_at(&self, depth: u32, general: &GeneralParams) -> f32 {
+        Self::scale(&self.angle, depth, general)
+    }
+
+    pub fn taper_at(&self, depth: u32, general: &GeneralParams) -> f32 {
+        Self::scale(&self.taper, depth, general)
+    }
+
+    pub fn frequency_at(&self, depth: u32, general: &GeneralParams) -> f32 {
+        Self::scale(&self.frequency, depth, general)
+    }
+
+    pub fn azimuth_spread_at(&self, depth: u32, general: &GeneralParams) -> f32 {
+        Self::scale(&self.azimuth_spread, depth, general)
+    }
+
+    /// Bend multiplier at `depth`. Unset, main limbs stay straight and
+    /// outer branches wander.
+    pub fn bend_at(&self, depth: u32, general: &GeneralParams) -> f32 {
+        match &self.bend {
+            Some(curve) => curve.evaluate(branch_order(depth, general)),
+            None => match depth {
+                0..=1 => 0.1,
+                2..=3 => 0.3,
+                4..=5 => 0.6,
+                _ => 1.0,
+            },
+        }
+    }
+
+    /// Segments a limb at `depth` may grow without forking, given the trunk's
+    /// own limit. Unset, the trunk and main limbs share the trunk's limit and
+    /// outer branches get a fixed few segments whatever the tree's size.
+    pub fn max_segments_at(&self, depth: u32, general: &GeneralParams, trunk_segments: u32) -> u32 {
+        match &self.length {
+            Some(curve) => ((trunk_segments as f32 * curve.evaluate(branch_order(depth, general))).round() as u32).max(1),
+            None => match depth {
+                0..=2 => trunk_segments,
+                3..=5 => 8,
+                6..=8 => 4,
+                9..=12 => 3,
+                13..=16 => 2,
+                _ => 1,
+            },
+        }
+    }
+
+    fn scale(curve: &Option<Curve>, depth: u32, general: &GeneralParams) -> f32 {
+        curve.as_ref().map_or(1.0, |curve| curve.evaluate(branch_order(depth, general)))
+    }
+}
+
+/// How the radius of a branch is divided between the children at a fork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RadiusModel {
+    #[default]
+    Fixed,      // Continuing axis keeps 95% of the radius, side branches 80%
+    PipeModel,  // Da Vinci rule: children share the parent's r^pipe_exponent by importance
+}
+
+impl RadiusModel {
+    pub const NAMES: &'static [&'static str] = &["fixed", "pipe_model"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RadiusModel::Fixed => "fixed",
+            RadiusModel::PipeModel => "pipe_model",
+        }
+    }
+}
+
+/// How side branches are arranged around their parent axis from one
+/// branching node to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phyllotaxis {
+    #[default]
+    Random,     // Uniformly random azimuth scaled by azimuth_variation
+    Alternate,  // One branch per node, alternating sides (distichous)
+    Opposite,   // Two branches per node facing each other, all in one plane
+    Decussate,  // Opposite pairs, each rotated 90 degrees from the last
+    Whorled,    // whorl_count branches evenly spaced, offset between nodes
+    Spiral,     // One branch per node, turned by divergence_angle each time
+}
+
+impl Phyllotaxis {
+    pub const NAMES: &'static [&'static str] = &["random", "alternate", "opposite", "decussate", "whorled", "spiral"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phyllotaxis::Random => "random",
+            Phyllotaxis::Alternate => "alternate",
+            Phyllotaxis::Opposite => "opposite",
+            Phyllotaxis::Decussate => "decussate",
+            Phyllotaxis::Whorled => "whorled",
+            Phyllotaxis::Spiral => "spiral",
+        }
+    }
+}
+
+/// The golden angle, 360 * (1 - 1/phi) degrees
+pub const GOLDEN_ANGLE: f32 = 137.507_77;
+
+/// Algorithm used to grow the branch skeleton from the trunk base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchingModel {
+    #[default]
+    RandomWalk,         // Segment-by-segment growth with random bends and forks
+    SpaceColonization,  // Growth pulled toward attraction points filling the crown
+    LSystem,            // Turtle interpretation of an L-system grammar
+    Growth,             // Year-by-year simulation of shoots, buds and thickening
+}
+
+impl BranchingModel {
+    pub const NAMES: &'static [&'static str] = &["random_walk", "space_colonization", "l_system", "growth"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BranchingModel::RandomWalk => "random_walk",
+            BranchingModel::SpaceColonization => "space_colonization",
+            BranchingModel::LSystem => "l_system",
+            BranchingModel::Growth => "growth",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpaceColonizationParams {
+    pub attraction_points: u32, // Points sampled in the crown envelope
+    pub crown_radius: f32,      // Horizontal radius of the ellipsoidal crown
+    pub crown_height: f32,      // Vertical extent of the crown, starting at the trunk split height
+    pub influence_radius: f32,  // Points within this distance attract a branch tip
+    pub kill_radius: f32,       // Points within this distance of a branch are consumed
+    pub iterations: u32,        // Maximum number of growth steps
+}
+
+/// Annual growth simulation used by the growth branching model. Every year
+/// each living bud extends a shoot, lateral buds formed the year before may
+/// break, and every existing segment thickens by one growth ring. The same
+/// seed grown to different ages gives the same individual at different
+/// stages of its life.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GrowthParams {
+    pub age: u32,               // Years of growth simulated
+    pub shoot_length: f32,      // Length of the leader's first annual shoot
+    pub lateral_vigor: f32,     // Shoot length of a lateral relative to the axis it grows from
+    pub vigor_decline: f32,     // Slows shoot growth with age: shoots at year n are 1 / (1 + decline * (n - 1)) as long
+    pub buds_per_shoot: u32,    // Lateral bud nodes formed along each annual shoot
+    pub bud_break: f32,         // Chance a lateral bud grows out the spring after it formed
+    pub ring_width: f32,        // Radius every segment gains each year
+}
+
+/// Competition for light in the growth model. Every grown segment casts a
+/// pyramid of shadow into a coarse voxel grid below it; each spring a bud's
+/// shoot length follows its light relative to the tree's other buds, and
+/// buds darker than `shade_tolerance` die. Other branching models ignore
+/// these settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LightParams {
+    pub enabled: bool,
+    pub voxel_size: f32,        // Edge length of a shadow grid cell
+    pub shadow_strength: f32,   // Shadow a segment adds to its own cell
+    pub shadow_depth: u32,      // Cells below a segment its shadow reaches
+    pub response: f32,          // How strongly shoot length follows relative light (0 ignores it)
+    pub shade_tolerance: f32,   // Buds with less light than this die (0-1)
+    pub occluders: Vec<Occluder>, // Walls, neighbouring trees and other obstacles
+}
+
+/// An axis-aligned box that shades the tree and that shoots cannot grow into.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Occluder {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+/// Environmental responses that steer growth while the skeleton is built.
+/// Depth scales set the strength on the outermost branch order relative to
+/// the trunk, interpolating linearly in between.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TropismParams {
+    pub light_direction: Vec3,  // Direction growth turns toward; need not be normalized
+    pub phototropism: f32,      // How strongly growth turns toward the light (0-1)
+    pub phototropism_depth_scale: f32, // Strength on the outermost branches relative to the trunk
+    pub gravitropism: f32,      // Turn toward the ground (positive, weeping) or away from it (negative)
+    pub gravitropism_depth_scale: f32, // Strength on the outermost branches relative to the trunk
+}
+
+/// Crown silhouette that bounds branch growth. The envelope is a solid of
+/// revolution around the trunk axis, starting at `base` and reaching up
+/// `height`; its radius at each height is `radius` times the shape profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnvelopeParams {
+    pub shape: EnvelopeShape,
+    pub mode: EnvelopeMode,     // What happens to growth leaving the envelope
+    pub base: f32,              // Height where the crown starts
+    pub height: f32,            // Vertical extent of the crown above its base
+    pub radius: f32,            // Widest radius of the crown
+    pub steer_strength: f32,    // How hard escaping growth is turned back in steer mode (0-1)
+    pub profile: Vec<f32>,      // Custom shape: radius fractions from base to top, evenly spaced
+}
+
+/// User-drawn paths for the main axes of a random walk tree. The trunk and
+/// each guided limb lay their cross-sections along their curve and end where
+/// it ends; side branches still grow from them procedurally. A limb guide
+/// starts from the trunk cross-section nearest its first point. Guides are
+/// rejected by validation with any other branching model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GuideParams {
+    pub trunk: Option<GuideCurve>,
+    pub branches: Vec<GuideCurve>,   // First-order limbs
+    pub keep_procedural_limbs: bool, // Also grow the usual trunk side branches next to guided limbs
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvelopeShape {
+    #[default]
+    None,           // No envelope; only max_reach limits growth
+    Cone,           // Widest at the base, narrowing to a point (spruce, fir)
+    Ellipsoid,      // Widest at mid-height (oak, lime)
+    Cylinder,       // Same width throughout (columnar poplar)
+    InvertedCone,   // Widest at the top (acacia, umbrella pine)
+    Flame,          // Widening to 70% of the height, then closing quickly (birch, cypress)
+    Custom,         // Piecewise linear `profile`
+}
+
+impl EnvelopeShape {
+    pub const NAMES: &'static [&'static str] = &["none", "cone", "ellipsoid", "cylinder", "inverted_cone", "flame", "custom"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnvelopeShape::None => "none",
+            EnvelopeShape::Cone => "cone",
+            EnvelopeShape::Ellipsoid => "ellipsoid",
+            EnvelopeShape::Cylinder => "cylinder",
+            EnvelopeShape::InvertedCone => "inverted_cone",
+            EnvelopeShape::Flame => "flame",
+            EnvelopeShape::Custom => "custom",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvelopeMode {
+    #[default]
+    Prune,  // Stop growing a limb when it leaves the envelope
+    Steer,  // Turn escaping growth back toward the inside
+}
+
+impl EnvelopeMode {
+    pub const NAMES: &'static [&'static str] = &["prune", "steer"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnvelopeMode::Prune => "prune",
+            EnvelopeMode::Steer => "steer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct RootParams {
     pub enable: bool,
     pub depth: f32,
@@ -46,7 +391,8 @@ pub struct RootParams {
     pub segment_length: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct TwigParams {
     pub enable: bool,
     pub density: f32,
@@ -54,23 +400,143 @@ pub struct TwigParams {
     pub angle_variation: f32,
 }
 
-impl Default for TreeParameters {
-    fn default() -> Self {
-        TreeParameters {
-            general: GeneralParams::default(),
-            trunk: TrunkParams::default(),
-            branching: BranchingParams::default(),
-            roots: RootParams::default(),
-            twigs: TwigParams::default(),
+/// One entry of the post-processing modifier stack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModifierEntry {
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    pub modifier: ModifierParams,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// A manual change to the skeleton, tagged by `op` in JSON, e.g.
+/// `{"op": "prune", "branch": 12, "distance": 1.5}`. Each edit is anchored
+/// to the cross-section `distance` along branch `branch` (measured like
+/// `Branch::length`) in the tree left by the edits before it, so changes
+/// that only add or remove cross-sections elsewhere keep it in place. An
+/// edit whose branch is gone, or no longer reaches that far, is skipped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SkeletonEdit {
+    Prune { branch: usize, distance: f32 },                          // Remove it and everything growing from it
+    Graft { branch: usize, distance: f32, direction: Vec3 },         // Grow a new limb from it
+    Move { branch: usize, distance: f32, offset: Vec3 },             // Shift the limb starting at it
+    Rotate { branch: usize, distance: f32, axis: Vec3, angle: f32 }, // Swing the limb around its base, in degrees
+}
+
+impl SkeletonEdit {
+    /// The branch and distance along it the edit is anchored to.
+    pub fn anchor(&self) -> (usize, f32) {
+        match self {
+            SkeletonEdit::Prune { branch, distance }
+            | SkeletonEdit::Graft { branch, distance, .. }
+            | SkeletonEdit::Move { branch, distance, .. }
+            | SkeletonEdit::Rotate { branch, distance, .. } => (*branch, *distance),
         }
     }
 }
 
+/// Modifier kind and its settings, tagged by `type` in JSON,
+/// e.g. `{"type": "gravity_sag", "strength": 0.3}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ModifierParams {
+    GravitySag(GravitySagParams),
+    WindBend(WindBendParams),
+    RandomPrune(RandomPruneParams),
+    RadiusNoise(RadiusNoiseParams),
+    Smoothing(SmoothingParams),
+    WeightSag(WeightSagParams),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GravitySagParams {
+    pub strength: f32,          // Downward bend per segment, scaled by branch depth (0.0-1.0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindBendParams {
+    pub direction: Vec3,        // Wind direction; only the horizontal part is used
+    pub strength: f32,          // Bend toward the wind, stronger on thin, high branches (0.0-1.0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RandomPruneParams {
+    pub probability: f32,       // Chance that a limb is removed (0.0-1.0)
+    pub min_depth: u32,         // Limbs shallower than this are never pruned
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RadiusNoiseParams {
+    pub amount: f32,            // Relative radius variation (0.0-1.0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmoothingParams {
+    pub iterations: u32,        // Smoothing passes (1-10)
+    pub strength: f32,          // How far each pass pulls a segment toward its neighbours (0.0-1.0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeightSagParams {
+    pub strength: f32,          // Bend under the estimated weight carried by each segment (0.0-1.0)
+}
+
+impl TreeParameters {
+    /// Parse a saved parameter file. Fields missing from the JSON keep their
+    /// `Default` value; unknown fields are rejected so typos don't go unnoticed.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize the full parameter set so it can be saved and reloaded exactly.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Apply a partial parameter file on top of these parameters, e.g.
+    /// `{"trunk": {"height": 6.0}, "twigs": {"enable": false}}`. Fields not
+    /// mentioned keep their current value.
+    pub fn with_json_patch(&self, json: &str) -> Result<Self, serde_json::Error> {
+        let patch: serde_json::Value = serde_json::from_str(json)?;
+        let mut merged = serde_json::to_value(self)?;
+        merge_json(&mut merged, patch);
+        serde_json::from_value(merged)
+    }
+}
+
+fn merge_json(target: &mut serde_json::Value, patch: serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
 impl Default for GeneralParams {
     fn default() -> Self {
         GeneralParams {
             seed: 123,
             max_depth: 20,
+            max_cross_sections: 100_000,
         }
     }
 }
@@ -101,6 +567,84 @@ impl Default for BranchingParams {
             radius_taper: 0.8,
             azimuth_variation: 0.5,
             max_reach: 50.0,
+            model: BranchingModel::RandomWalk,
+            phyllotaxis: Phyllotaxis::Random,
+            whorl_count: 3,
+            divergence_angle: GOLDEN_ANGLE,
+            codominant_probability: 0.0,
+            codominant_count: 2,
+            codominant_angle: 30.0,
+            radius_model: RadiusModel::Fixed,
+            pipe_exponent: 2.0,
+            lateral_importance: 0.5,
+            curves: DepthCurves::default(),
+        }
+    }
+}
+
+impl Default for SpaceColonizationParams {
+    fn default() -> Self {
+        SpaceColonizationParams {
+            attraction_points: 800,
+            crown_radius: 4.0,
+            crown_height: 6.0,
+            influence_radius: 3.0,
+            kill_radius: 0.6,
+            iterations: 300,
+        }
+    }
+}
+
+impl Default for GrowthParams {
+    fn default() -> Self {
+        GrowthParams {
+            age: 15,
+            shoot_length: 0.8,
+            lateral_vigor: 0.6,
+            vigor_decline: 0.03,
+            buds_per_shoot: 2,
+            bud_break: 0.6,
+            ring_width: 0.01,
+        }
+    }
+}
+
+impl Default for LightParams {
+    fn default() -> Self {
+        LightParams {
+            enabled: false,
+            voxel_size: 0.5,
+            shadow_strength: 0.1,
+            shadow_depth: 6,
+            response: 1.0,
+            shade_tolerance: 0.1,
+            occluders: Vec::new(),
+        }
+    }
+}
+
+impl Default for TropismParams {
+    fn default() -> Self {
+        TropismParams {
+            light_direction: Vec3::Y,
+            phototropism: 0.0,
+            phototropism_depth_scale: 1.0,
+            gravitropism: 0.0,
+            gravitropism_depth_scale: 1.0,
+        }
+    }
+}
+
+impl Default for EnvelopeParams {
+    fn default() -> Self {
+        EnvelopeParams {
+            shape: EnvelopeShape::None,
+            mode: EnvelopeMode::Prune,
+            base: 2.0,
+            height: 10.0,
+            radius: 5.0,
+            steer_strength: 0.5,
+            profile: vec![0.6, 1.0, 0.8, 0.4, 0.0],
         }
     }
 }
@@ -128,4 +672,112 @@ impl Default for TwigParams {
     }
 }
 
-// Legacy compatibility layer removed - no longer needed after refactoring
\ No newline at end of file
+impl Default for GravitySagParams {
+    fn default() -> Self {
+        GravitySagParams {
+            strength: 0.3,
+        }
+    }
+}
+
+impl Default for WindBendParams {
+    fn default() -> Self {
+        WindBendParams {
+            direction: Vec3::X,
+            strength: 0.3,
+        }
+    }
+}
+
+impl Default for RandomPruneParams {
+    fn default() -> Self {
+        RandomPruneParams {
+            probability: 0.2,
+            min_depth: 2,
+        }
+    }
+}
+
+impl Default for RadiusNoiseParams {
+    fn default() -> Self {
+        RadiusNoiseParams {
+            amount: 0.15,
+        }
+    }
+}
+
+impl Default for SmoothingParams {
+    fn default() -> Self {
+        SmoothingParams {
+            iterations: 2,
+            strength: 0.5,
+        }
+    }
+}
+
+impl Default for WeightSagParams {
+    fn default() -> Self {
+        WeightSagParams {
+            strength: 0.5,
+        }
+    }
+}
+
+// Legacy compatibility layer removed - no longer needed after refactoring
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The tables the random walk used before depth curves existed
+    fn table_segments(depth: u32, trunk_segments: u32) -> u32 {
+        match depth {
+            0..=2 => trunk_segments,
+            3..=5 => 8,
+            6..=8 => 4,
+            9..=12 => 3,
+            13..=16 => 2,
+            _ => 1,
+        }
+    }
+
+    fn table_bend(depth: u32) -> f32 {
+        match depth {
+            0..=1 => 0.1,
+            2..=3 => 0.3,
+            4..=5 => 0.6,
+            _ => 1.0,
+        }
+    }
+
+    #[test]
+    fn default_curves_match_the_old_depth_tables() {
+        let curves = DepthCurves::default();
+        for max_depth in [1, 3, 8, 10, 20] {
+            let general = GeneralParams { max_depth, ..GeneralParams::default() };
+            for depth in 0..max_depth {
+                for trunk_segments in [20, 33, 70] {
+                    assert_eq!(curves.max_segments_at(depth, &general, trunk_segments), table_segments(depth, trunk_segments));
+                }
+                assert_eq!(curves.bend_at(depth, &general), table_bend(depth));
+                assert_eq!(curves.angle_at(depth, &general), 1.0);
+                assert_eq!(curves.taper_at(depth, &general), 1.0);
+                assert_eq!(curves.frequency_at(depth, &general), 1.0);
+                assert_eq!(curves.azimuth_spread_at(depth, &general), 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn set_curves_run_over_the_branch_order() {
+        let curves = DepthCurves {
+            bend: Some(Curve::new(&[[0.0, 0.0], [1.0, 2.0]])),
+            length: Some(Curve::constant(0.5)),
+            ..DepthCurves::default()
+        };
+        let general = GeneralParams { max_depth: 4, ..GeneralParams::default() };
+        assert_eq!(curves.bend_at(1, &general), 0.5);
+        assert_eq!(curves.bend_at(4, &general), 2.0);
+        assert_eq!(curves.max_segments_at(3, &general, 30), 15);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::core::{
+    BranchingParams, GeneralParams, ModifierEntry, ModifierParams, Phyllotaxis, RootParams,
+    TreeParameters, TrunkParams, TwigParams, WeightSagParams,
+};
+
+/// Names of the built-in species presets, in the order the designer lists them.
+pub const PRESET_NAMES: &[&str] = &["fir", "birch", "ivy"];
+
+impl TreeParameters {
+    /// Look up a built-in species preset by name (case-insensitive).
+    pub fn preset(name: &str) -> Option<TreeParameters> {
+        match name.to_ascii_lowercase().as_str() {
+            "fir" => Some(fir()),
+            "birch" => Some(birch()),
+            "ivy" => Some(ivy()),
+            _ => None,
+        }
+    }
+
+    pub fn preset_names() -> &'static [&'static str] {
+        PRESET_NAMES
+    }
+}
+
+/// Tall conifer: a single straight leader with short, near-horizontal side branches.
+fn fir() -> TreeParameters {
+    TreeParameters {
+        general: GeneralParams {
+            max_depth: 10,
+            ..GeneralParams::default()
+        },
+        trunk: TrunkParams {
+            height: 12.0,
+            buttressing: 0.5,
+            split_height: 2.0,
+            segment_length: 0.4,
+            size: 1.2,
+            ring_spread: 0.2,
+            segment_length_variation: 0.1,
+        },
+        branching: BranchingParams {
+            angle_min: 60.0,
+            angle_max: 85.0,
+            bend_angle_min: -5.0,
+            bend_angle_max: 5.0,
+            frequency_min: 1,
+            frequency_max: 3,
+            radius_taper: 0.75,
+            azimuth_variation: 0.2,
+            max_reach: 8.0,
+            phyllotaxis: Phyllotaxis::Whorled,
+            whorl_count: 5,
+            ..BranchingParams::default()
+        },
+        roots: RootParams {
+            enable: true,
+            depth: 1.0,
+            spread: 1.5,
+            density: 5,
+            segment_length: 0.3,
+        },
+        twigs: TwigParams {
+            enable: true,
+            density: 1.6,
+            scale: 0.6,
+            angle_variation: 0.3,
+        },
+        // Fir limbs droop toward their tips under their own weight
+        modifiers: vec![ModifierEntry {
+            enabled: true,
+            modifier: ModifierParams::WeightSag(WeightSagParams { strength: 0.4 }),
+        }],
+        ..TreeParameters::default()
+    }
+}
+
+/// Slender deciduous tree with a high, open crown of upward-angled branches.
+fn birch() -> TreeParameters {
+    TreeParameters {
+        general: GeneralParams {
+            max_depth: 9,
+            ..GeneralParams::default()
+        },
+        trunk: TrunkParams {
+            height: 8.0,
+            buttressing: 0.6,
+            split_height: 4.0,
+            segment_length: 0.35,
+            size: 0.8,
+            ring_spread: 0.2,
+            segment_length_variation: 0.25,
+        },
+        branching: BranchingParams {
+            angle_min: 20.0,
+            angle_max: 40.0,
+            bend_angle_min: -10.0,
+            bend_angle_max: 20.0,
+            frequency_min: 2,
+            frequency_max: 4,
+            radius_taper: 0.8,
+            azimuth_variation: 0.3,
+            max_reach: 12.0,
+            phyllotaxis: Phyllotaxis::Spiral,
+            ..BranchingParams::default()
+        },
+        roots: RootParams {
+            enable: true,
+            depth: 1.2,
+            spread: 1.2,
+            density: 4,
+            segment_length: 0.3,
+        },
+        twigs: TwigParams {
+            enable: true,
+            density: 1.2,
+            scale: 0.7,
+            angle_variation: 0.7,
+        },
+        ..TreeParameters::default()
+    }
+}
+
+/// Low, sprawling climber: thin, strongly bending stems covered in leaves.
+fn ivy() -> TreeParameters {
+    TreeParameters {
+        general: GeneralParams {
+            max_depth: 12,
+            ..GeneralParams::default()
+        },
+        trunk: TrunkParams {
+            height: 1.5,
+            buttressing: 0.3,
+            split_height: 0.3,
+            segment_length: 0.25,
+            size: 0.15,
+            ring_spread: 0.0,
+            segment_length_variation: 0.4,
+        },
+        branching: BranchingParams {
+            angle_min: 30.0,
+            angle_max: 70.0,
+            bend_angle_min: -35.0,
+            bend_angle_max: 35.0,
+            frequency_min: 1,
+            frequency_max: 3,
+            radius_taper: 0.9,
+            azimuth_variation: 1.0,
+            max_reach: 6.0,
+            ..BranchingParams::default()
+        },
+        roots: RootParams {
+            enable: true,
+            depth: 0.5,
+            spread: 0.5,
+            density: 2,
+            segment_length: 0.1,
+        },
+        twigs: TwigParams {
+            enable: true,
+            density: 2.0,
+            scale: 0.5,
+            angle_variation: 1.0,
+        },
+        ..TreeParameters::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_preset_validates() {
+        for name in PRESET_NAMES {
+            let params = TreeParameters::preset(name).expect("listed preset exists");
+            assert!(params.validate().is_empty(), "preset {} has violations: {:?}", name, params.validate());
+        }
+    }
+}
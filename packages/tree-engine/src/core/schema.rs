@@ -0,0 +1,282 @@
+use crate::core::{BranchingModel, EnvelopeMode, EnvelopeShape, Phyllotaxis, RadiusModel, TreeParameters};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamKind {
+    Float,
+    Int,
+    Bool,
+    Choice,   // One of `options`, exchanged as a string
+}
+
+/// Static description of a single tree parameter. The range is the one the
+/// engine validates against, so UIs generated from it never drift from the engine.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ParamSpec {
+    pub name: &'static str,        // Dotted path into TreeParameters, e.g. "trunk.height"
+    pub group: &'static str,       // Top-level TreeParameters field the parameter belongs to
+    #[serde(rename = "type")]
+    pub kind: ParamKind,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub options: &'static [&'static str],
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    Float(f32),
+    Int(u32),
+    Bool(bool),
+    Choice(&'static str),
+}
+
+/// Schema entry as exported to the frontend: the static spec plus its default value.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamSchemaEntry {
+    #[serde(flatten)]
+    pub spec: ParamSpec,
+    pub default: ParamValue,
+}
+
+const fn float(
+    name: &'static str,
+    group: &'static str,
+    min: f64,
+    max: f64,
+    step: f64,
+    description: &'static str,
+) -> ParamSpec {
+    ParamSpec { name, group, kind: ParamKind::Float, min, max, step, options: &[], description }
+}
+
+const fn int(
+    name: &'static str,
+    group: &'static str,
+    min: f64,
+    max: f64,
+    description: &'static str,
+) -> ParamSpec {
+    ParamSpec { name, group, kind: ParamKind::Int, min, max, step: 1.0, options: &[], description }
+}
+
+const fn boolean(name: &'static str, group: &'static str, description: &'static str) -> ParamSpec {
+    ParamSpec { name, group, kind: ParamKind::Bool, min: 0.0, max: 1.0, step: 1.0, options: &[], description }
+}
+
+const fn choice(
+    name: &'static str,
+    group: &'static str,
+    options: &'static [&'static str],
+    description: &'static str,
+) -> ParamSpec {
+    let max = (options.len() - 1) as f64;
+    ParamSpec { name, group, kind: ParamKind::Choice, min: 0.0, max, step: 1.0, options, description }
+}
+
+pub const PARAM_SPECS: &[ParamSpec] = &[
+    // General
+    int("general.seed", "general", 0.0, u32::MAX as f64, "Random seed; the same seed always produces the same tree"),
+    int("general.max_depth", "general", 1.0, 20.0, "Maximum branching depth"),
+    int("general.max_cross_sections", "general", 100.0, 1_000_000.0, "Budget of cross-sections; branch growth stops when it is reached"),
+    // Trunk
+    float("trunk.height", "trunk", 0.1, 30.0, 0.1, "Height of the main trunk"),
+    float("trunk.buttressing", "trunk", 0.1, 5.0, 0.1, "Number and spread of buttress roots around the trunk base"),
+    float("trunk.split_height", "trunk", 0.0, 30.0, 0.1, "Height below which no branches are created"),
+    float("trunk.segment_length", "trunk", 0.01, 1.0, 0.01, "Length of each generated segment"),
+    float("trunk.size", "trunk", 0.1, 10.0, 0.1, "Trunk thickness multiplier"),
+    float("trunk.ring_spread", "trunk", 0.0, 2.0, 0.05, "Spread of the buttress rings around the trunk center"),
+    float("trunk.segment_length_variation", "trunk", 0.0, 1.0, 0.05, "Random variation of segment length"),
+    // Branching
+    float("branching.angle_min", "branching", 0.0, 90.0, 1.0, "Minimum angle (degrees) between a side branch and its parent"),
+    float("branching.angle_max", "branching", 0.0, 90.0, 1.0, "Maximum angle (degrees) between a side branch and its parent"),
+    float("branching.bend_angle_min", "branching", -45.0, 45.0, 1.0, "Minimum per-segment bend (degrees)"),
+    float("branching.bend_angle_max", "branching", -45.0, 45.0, 1.0, "Maximum per-segment bend (degrees)"),
+    int("branching.frequency_min", "branching", 1.0, 15.0, "Minimum number of segments between branch points"),
+    int("branching.frequency_max", "branching", 1.0, 15.0, "Maximum number of segments between branch points"),
+    float("branching.radius_taper", "branching", 0.1, 1.0, 0.01, "Radius kept per segment; lower values taper faster"),
+    float("branching.azimuth_variation", "branching", 0.0, 1.0, 0.05, "How far side branches spread around their parent (0 = planar)"),
+    float("branching.max_reach", "branching", 2.0, 50.0, 0.5, "Maximum distance branches may grow from the trunk"),
+    choice("branching.model", "branching", BranchingModel::NAMES, "Growth algorithm used for the branch skeleton"),
+    choice("branching.phyllotaxis", "branching", Phyllotaxis::NAMES, "Arrangement of side branches around their parent"),
+    int("branching.whorl_count", "branching", 2.0, 8.0, "Side branches per node for whorled phyllotaxis"),
+    float("branching.divergence_angle", "branching", 0.0, 360.0, 0.5, "Angle between successive nodes for spiral phyllotaxis"),
+    float("branching.codominant_probability", "branching", 0.0, 1.0, 0.01, "Chance a branching node splits into equal co-dominant stems"),
+    int("branching.codominant_count", "branching", 2.0, 7.0, "Number of stems at a co-dominant fork"),
+    float("branching.codominant_angle", "branching", 0.0, 90.0, 1.0, "Angle between each co-dominant stem and the parent axis"),
+    choice("branching.radius_model", "branching", RadiusModel::NAMES, "How a fork divides its radius between the children"),
+    float("branching.pipe_exponent", "branching", 1.5, 3.5, 0.05, "Pipe model exponent; 2 conserves cross-sectional area"),
+    float("branching.lateral_importance", "branching", 0.05, 1.0, 0.01, "Pipe model share of a side branch relative to the continuing axis"),
+    // Space colonization (used when branching.model is space_colonization)
+    int("space_colonization.attraction_points", "space_colonization", 50.0, 5000.0, "Number of attraction points sampled in the crown"),
+    float("space_colonization.crown_radius", "space_colonization", 0.5, 30.0, 0.1, "Horizontal radius of the crown envelope"),
+    float("space_colonization.crown_height", "space_colonization", 0.5, 40.0, 0.1, "Vertical extent of the crown envelope, starting at the split height"),
+    float("space_colonization.influence_radius", "space_colonization", 0.5, 20.0, 0.1, "Distance within which attraction points pull on a branch tip"),
+    float("space_colonization.kill_radius", "space_colonization", 0.05, 5.0, 0.05, "Attraction points this close to a branch are consumed"),
+    int("space_colonization.iterations", "space_colonization", 10.0, 1000.0, "Maximum number of growth iterations"),
+    // Growth simulation (used when branching.model is growth)
+    int("growth.age", "growth", 1.0, 200.0, "Years of growth simulated; the same seed gives the same tree at every age"),
+    float("growth.shoot_length", "growth", 0.05, 3.0, 0.01, "Length of the leader's first annual shoot"),
+    float("growth.lateral_vigor", "growth", 0.1, 1.0, 0.01, "Shoot length of a lateral relative to the axis it grows from"),
+    float("growth.vigor_decline", "growth", 0.0, 0.5, 0.005, "How quickly annual shoots get shorter as the tree ages"),
+    int("growth.buds_per_shoot", "growth", 0.0, 8.0, "Lateral bud nodes formed along each annual shoot"),
+    float("growth.bud_break", "growth", 0.0, 1.0, 0.01, "Chance a lateral bud grows out the spring after it formed"),
+    float("growth.ring_width", "growth", 0.0, 0.05, 0.001, "Radius every segment gains each year"),
+    // Light competition (used when branching.model is growth; occluders are a list)
+    boolean("light.enabled", "light", "Let buds compete for light: shaded buds grow less or die"),
+    float("light.voxel_size", "light", 0.1, 5.0, 0.05, "Edge length of a shadow grid cell"),
+    float("light.shadow_strength", "light", 0.0, 1.0, 0.01, "Shadow every grown segment adds to its cell"),
+    int("light.shadow_depth", "light", 1.0, 20.0, "Cells below a segment its shadow reaches"),
+    float("light.response", "light", 0.0, 3.0, 0.05, "How strongly shoot length follows the light a bud receives"),
+    float("light.shade_tolerance", "light", 0.0, 1.0, 0.01, "Buds receiving less light than this die"),
+    // Tropism
+    float("tropism.phototropism", "tropism", 0.0, 1.0, 0.01, "How strongly growth turns toward the light direction"),
+    float("tropism.phototropism_depth_scale", "tropism", 0.0, 3.0, 0.05, "Phototropism on the outermost branches relative to the trunk"),
+    float("tropism.gravitropism", "tropism", -1.0, 1.0, 0.01, "Turn toward the ground (positive) or upward (negative) while growing"),
+    float("tropism.gravitropism_depth_scale", "tropism", 0.0, 3.0, 0.05, "Gravitropism on the outermost branches relative to the trunk"),
+    // Crown envelope
+    choice("envelope.shape", "envelope", EnvelopeShape::NAMES, "Crown silhouette that bounds branch growth"),
+    choice("envelope.mode", "envelope", EnvelopeMode::NAMES, "Prune limbs leaving the envelope or steer them back inside"),
+    float("envelope.base", "envelope", 0.0, 30.0, 0.1, "Height where the crown envelope starts"),
+    float("envelope.height", "envelope", 0.5, 40.0, 0.1, "Vertical extent of the crown envelope"),
+    float("envelope.radius", "envelope", 0.5, 30.0, 0.1, "Widest radius of the crown envelope"),
+    float("envelope.steer_strength", "envelope", 0.0, 1.0, 0.01, "How hard escaping growth is turned back in steer mode"),
+    // Guide curves (the curves themselves are lists of points)
+    boolean("guides.keep_procedural_limbs", "guides", "Grow procedural first-order branches next to the guided limbs"),
+    // L-system (used when branching.model is l_system; axiom and rules are text)
+    int("l_system.iterations", "l_system", 0.0, 8.0, "Number of rewriting passes applied to the axiom"),
+    float("l_system.angle", "l_system", 0.0, 180.0, 0.5, "Default turn angle for turtle rotations"),
+    float("l_system.length", "l_system", 0.05, 5.0, 0.05, "Default length of a drawn segment"),
+    float("l_system.radius_scale", "l_system", 0.1, 1.0, 0.01, "Default radius factor applied by '!'"),
+    float("l_system.length_scale", "l_system", 0.1, 1.0, 0.01, "Default length factor applied by '\"'"),
+    // Roots
+    boolean("roots.enable", "roots", "Generate the root system"),
+    float("roots.depth", "roots", 0.5, 3.0, 0.1, "How deep the roots reach"),
+    float("roots.spread", "roots", 0.5, 3.0, 0.1, "How far the roots spread"),
+    int("roots.density", "roots", 2.0, 8.0, "Number of roots"),
+    float("roots.segment_length", "roots", 0.1, 0.8, 0.05, "Length of each root segment"),
+    // Twigs
+    boolean("twigs.enable", "twigs", "Generate twigs"),
+    float("twigs.density", "twigs", 0.1, 2.0, 0.05, "Twig density on thin branches"),
+    float("twigs.scale", "twigs", 0.1, 3.0, 0.05, "Twig size multiplier"),
+    float("twigs.angle_variation", "twigs", 0.0, 1.0, 0.05, "Random variation of twig angles"),
+];
+
+impl ParamSpec {
+    /// Look up the spec for a dotted parameter name.
+    pub fn find(name: &str) -> Option<&'static ParamSpec> {
+        PARAM_SPECS.iter().find(|spec| spec.name == name)
+    }
+}
+
+impl ParamValue {
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            ParamValue::Float(value) => value as f64,
+            ParamValue::Int(value) => value as f64,
+            ParamValue::Bool(value) => if value { 1.0 } else { 0.0 },
+            ParamValue::Choice(_) => f64::NAN,
+        }
+    }
+}
+
+impl TreeParameters {
+    /// Full parameter schema with defaults taken from `TreeParameters::default()`.
+    pub fn schema() -> Vec<ParamSchemaEntry> {
+        let defaults = TreeParameters::default();
+        PARAM_SPECS
+            .iter()
+            .map(|spec| ParamSchemaEntry {
+                spec: *spec,
+                default: defaults.get(spec.name).expect("every schema entry maps to a field"),
+            })
+            .collect()
+    }
+
+    /// Read a parameter by its dotted schema name.
+    pub fn get(&self, name: &str) -> Option<ParamValue> {
+        use ParamValue::*;
+
+        let value = match name {
+            "general.seed" => Int(self.general.seed),
+            "general.max_depth" => Int(self.general.max_depth),
+            "general.max_cross_sections" => Int(self.general.max_cross_sections),
+            "trunk.height" => Float(self.trunk.height),
+            "trunk.buttressing" => Float(self.trunk.buttressing),
+            "trunk.split_height" => Float(self.trunk.split_height),
+            "trunk.segment_length" => Float(self.trunk.segment_length),
+            "trunk.size" => Float(self.trunk.size),
+            "trunk.ring_spread" => Float(self.trunk.ring_spread),
+            "trunk.segment_length_variation" => Float(self.trunk.segment_length_variation),
+            "branching.angle_min" => Float(self.branching.angle_min),
+            "branching.angle_max" => Float(self.branching.angle_max),
+            "branching.bend_angle_min" => Float(self.branching.bend_angle_min),
+            "branching.bend_angle_max" => Float(self.branching.bend_angle_max),
+            "branching.frequency_min" => Int(self.branching.frequency_min),
+            "branching.frequency_max" => Int(self.branching.frequency_max),
+            "branching.radius_taper" => Float(self.branching.radius_taper),
+            "branching.azimuth_variation" => Float(self.branching.azimuth_variation),
+            "branching.max_reach" => Float(self.branching.max_reach),
+            "branching.model" => Choice(self.branching.model.name()),
+            "branching.phyllotaxis" => Choice(self.branching.phyllotaxis.name()),
+            "branching.whorl_count" => Int(self.branching.whorl_count),
+            "branching.divergence_angle" => Float(self.branching.divergence_angle),
+            "branching.codominant_probability" => Float(self.branching.codominant_probability),
+            "branching.codominant_count" => Int(self.branching.codominant_count),
+            "branching.codominant_angle" => Float(self.branching.codominant_angle),
+            "branching.radius_model" => Choice(self.branching.radius_model.name()),
+            "branching.pipe_exponent" => Float(self.branching.pipe_exponent),
+            "branching.lateral_importance" => Float(self.branching.lateral_importance),
+            "space_colonization.attraction_points" => Int(self.space_colonization.attraction_points),
+            "space_colonization.crown_radius" => Float(self.space_colonization.crown_radius),
+            "space_colonization.crown_height" => Float(self.space_colonization.crown_height),
+            "space_colonization.influence_radius" => Float(self.space_colonization.influence_radius),
+            "space_colonization.kill_radius" => Float(self.space_colonization.kill_radius),
+            "space_colonization.iterations" => Int(self.space_colonization.iterations),
+            "growth.age" => Int(self.growth.age),
+            "growth.shoot_length" => Float(self.growth.shoot_length),
+            "growth.lateral_vigor" => Float(self.growth.lateral_vigor),
+            "growth.vigor_decline" => Float(self.growth.vigor_decline),
+            "growth.buds_per_shoot" => Int(self.growth.buds_per_shoot),
+            "growth.bud_break" => Float(self.growth.bud_break),
+            "growth.ring_width" => Float(self.growth.ring_width),
+            "light.enabled" => Bool(self.light.enabled),
+            "light.voxel_size" => Float(self.light.voxel_size),
+            "light.shadow_strength" => Float(self.light.shadow_strength),
+            "light.shadow_depth" => Int(self.light.shadow_depth),
+            "light.response" => Float(self.light.response),
+            "light.shade_tolerance" => Float(self.light.shade_tolerance),
+            "tropism.phototropism" => Float(self.tropism.phototropism),
+            "tropism.phototropism_depth_scale" => Float(self.tropism.phototropism_depth_scale),
+            "tropism.gravitropism" => Float(self.tropism.gravitropism),
+            "tropism.gravitropism_depth_scale" => Float(self.tropism.gravitropism_depth_scale),
+            "envelope.shape" => Choice(self.envelope.shape.name()),
+            "envelope.mode" => Choice(self.envelope.mode.name()),
+            "envelope.base" => Float(self.envelope.base),
+            "envelope.height" => Float(self.envelope.height),
+            "envelope.radius" => Float(self.envelope.radius),
+            "envelope.steer_strength" => Float(self.envelope.steer_strength),
+            "l_system.iterations" => Int(self.l_system.iterations),
+            "l_system.angle" => Float(self.l_system.angle),
+            "l_system.length" => Float(self.l_system.length),
+            "l_system.radius_scale" => Float(self.l_system.radius_scale),
+            "l_system.length_scale" => Float(self.l_system.length_scale),
+            "guides.keep_procedural_limbs" => Bool(self.guides.keep_procedural_limbs),
+            "roots.enable" => Bool(self.roots.enable),
+            "roots.depth" => Float(self.roots.depth),
+            "roots.spread" => Float(self.roots.spread),
+            "roots.density" => Int(self.roots.density),
+            "roots.segment_length" => Float(self.roots.segment_length),
+            "twigs.enable" => Bool(self.twigs.enable),
+            "twigs.density" => Float(self.twigs.density),
+            "twigs.scale" => Float(self.twigs.scale),
+            "twigs.angle_variation" => Float(self.twigs.angle_variation),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
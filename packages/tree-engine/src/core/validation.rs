@@ -0,0 +1,298 @@
+use crate::core::{BranchingModel, DepthCurves, EnvelopeShape, ModifierParams, SkeletonEdit, TreeParameters};
+use crate::core::schema::{ParamKind, ParamSpec, PARAM_SPECS};
+use serde::Serialize;
+use std::fmt;
+
+/// Longest axiom or rule successor accepted, in modules
+const MAX_GRAMMAR_MODULES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationReason {
+    NotANumber,   // NaN or infinite value
+    OutOfRange,   // Outside the schema range
+    Ordering,     // Breaks a cross-field rule such as min <= max
+    Unsupported,  // Set, but ignored by the selected branching model
+    Malformed,    // Wrong shape, e.g. an unknown name or an odd number of coordinates
+}
+
+/// A single parameter that failed validation, with the range it has to lie in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParamViolation {
+    pub field: String,          // Dotted schema name, or e.g. "modifiers[0].strength"
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+    pub reason: ViolationReason,
+    pub message: String,
+}
+
+impl ParamViolation {
+    /// A value with the wrong shape rather than one out of range; `value` is
+    /// its size, e.g. the number of coordinates given.
+    pub fn malformed(field: String, value: f64, message: String) -> Self {
+        ParamViolation { field, value, min: 0.0, max: 0.0, reason: ViolationReason::Malformed, message }
+    }
+}
+
+/// Error returned when loading or changing parameters.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ParameterError {
+    Parse { message: String },
+    Validation { message: String, violations: Vec<ParamViolation> },
+}
+
+impl ParameterError {
+    pub fn parse(error: serde_json::Error) -> Self {
+        ParameterError::Parse { message: format!("Invalid tree parameters: {}", error) }
+    }
+
+    pub fn validation(violations: Vec<ParamViolation>) -> Self {
+        let details: Vec<&str> = violations.iter().map(|v| v.message.as_str()).collect();
+        ParameterError::Validation {
+            message: format!("Invalid tree parameters: {}", details.join("; ")),
+            violations,
+        }
+    }
+}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterError::Parse { message } | ParameterError::Validation { message, .. } => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParameterError {}
+
+impl TreeParameters {
+    /// Check every parameter against its schema range, the modifier settings
+    /// against theirs, plus the cross-field rules (split height within the trunk,
+    /// min <= max for each range). Returns an empty list when the parameters are valid.
+    pub fn validate(&self) -> Vec<ParamViolation> {
+        let mut violations = Vec::new();
+
+        // Choice parameters are already checked by deserialization
+        for spec in PARAM_SPECS.iter().filter(|spec| spec.kind != ParamKind::Choice) {
+            if let Some(value) = self.get(spec.name) {
+                check_range(spec.name.to_string(), value.as_f64(), spec.min, spec.max, &mut violations);
+            }
+        }
+
+        for (index, entry) in self.modifiers.iter().enumerate() {
+            let field = |name: &str| format!("modifiers[{}].{}", index, name);
+            match &entry.modifier {
+                ModifierParams::GravitySag(params) => {
+                    check_range(field("strength"), params.strength as f64, 0.0, 1.0, &mut violations);
+                }
+                ModifierParams::WindBend(params) => {
+                    check_range(field("strength"), params.strength as f64, 0.0, 1.0, &mut violations);
+                }
+                ModifierParams::RandomPrune(params) => {
+                    check_range(field("probability"), params.probability as f64, 0.0, 1.0, &mut violations);
+                    check_range(field("min_depth"), params.min_depth as f64, 1.0, 20.0, &mut violations);
+                }
+                ModifierParams::RadiusNoise(params) => {
+                    check_range(field("amount"), params.amount as f64, 0.0, 1.0, &mut violations);
+                }
+                ModifierParams::Smoothing(params) => {
+                    check_range(field("iterations"), params.iterations as f64, 1.0, 10.0, &mut violations);
+                    check_range(field("strength"), params.strength as f64, 0.0, 1.0, &mut violations);
+                }
+                ModifierParams::WeightSag(params) => {
+                    check_range(field("strength"), params.strength as f64, 0.0, 1.0, &mut violations);
+                }
+            }
+        }
+
+        if self.envelope.shape == EnvelopeShape::Custom {
+            let points = self.envelope.profile.len() as f64;
+            check_range("envelope.profile".to_string(), points, 2.0, 64.0, &mut violations);
+        }
+        for (index, fraction) in self.envelope.profile.iter().enumerate() {
+            check_range(format!("envelope.profile[{}]", index), *fraction as f64, 0.0, 1.0, &mut violations);
+        }
+
+        for name in DepthCurves::NAMES {
+            let Some(curve) = self.branching.curves.curve(name) else { continue };
+            let mut previous = 0.0;
+            for (index, [x, y]) in curve.0.iter().enumerate() {
+                let field = |axis: &str| format!("branching.curves.{}[{}].{}", name, index, axis);
+                // Points must be in order along the branch order axis
+                check_range(field("x"), *x as f64, previous, 1.0, &mut violations);
+                check_range(field("y"), *y as f64, 0.0, 10.0, &mut violations);
+                previous = (*x as f64).max(previous);
+            }
+        }
+
+        for (index, occluder) in self.light.occluders.iter().enumerate() {
+            for (axis, min, max) in [("x", occluder.min.x, occluder.max.x), ("y", occluder.min.y, occluder.max.y), ("z", occluder.min.z, occluder.max.z)] {
+                check_range(format!("light.occluders[{}].min.{}", index, axis), min as f64, f64::MIN, f64::MAX, &mut violations);
+                // A box must not be inside out
+                check_range(format!("light.occluders[{}].max.{}", index, axis), max as f64, min as f64, f64::MAX, &mut violations);
+            }
+        }
+
+        let guides = self.guides.trunk.iter().map(|guide| ("guides.trunk".to_string(), guide));
+        let limbs = self.guides.branches.iter().enumerate().map(|(index, guide)| (format!("guides.branches[{}]", index), guide));
+        for (name, guide) in guides.chain(limbs) {
+            check_range(format!("{}.points", name), guide.points.len() as f64, 2.0, 1000.0, &mut violations);
+            for (index, point) in guide.points.iter().enumerate() {
+                for (axis, value) in ["x", "y", "z"].into_iter().zip(point.to_array()) {
+                    check_range(format!("{}.points[{}].{}", name, index, axis), value as f64, -1000.0, 1000.0, &mut violations);
+                }
+            }
+        }
+
+        // Only the random walk follows guides; other models would silently drop them
+        let guide_count = self.guides.branches.len() + usize::from(self.guides.trunk.is_some());
+        if guide_count > 0 && self.branching.model != BranchingModel::RandomWalk {
+            violations.push(ParamViolation {
+                field: "guides".to_string(),
+                value: guide_count as f64,
+                min: 0.0,
+                max: 0.0,
+                reason: ViolationReason::Unsupported,
+                message: format!(
+                    "guides: {} curve(s) are only followed by the random_walk branching model, not {}",
+                    guide_count,
+                    self.branching.model.name()
+                ),
+            });
+        }
+
+        for (index, edit) in self.edits.iter().enumerate() {
+            let field = |name: &str| format!("edits[{}].{}", index, name);
+            check_range(field("distance"), edit.anchor().1 as f64, 0.0, 1000.0, &mut violations);
+            let vector = match edit {
+                SkeletonEdit::Prune { .. } => None,
+                SkeletonEdit::Graft { direction, .. } => Some(("direction", *direction)),
+                SkeletonEdit::Move { offset, .. } => Some(("offset", *offset)),
+                SkeletonEdit::Rotate { axis, angle, .. } => {
+                    check_range(field("angle"), *angle as f64, -360.0, 360.0, &mut violations);
+                    Some(("axis", *axis))
+                }
+            };
+            if let Some((name, vector)) = vector {
+                for (axis, value) in ["x", "y", "z"].into_iter().zip(vector.to_array()) {
+                    check_range(field(&format!("{}.{}", name, axis)), value as f64, -1000.0, 1000.0, &mut violations);
+                }
+            }
+        }
+
+        let max_modules = MAX_GRAMMAR_MODULES as f64;
+        check_range("l_system.axiom".to_string(), self.l_system.axiom.0.len() as f64, 0.0, max_modules, &mut violations);
+        for (index, rule) in self.l_system.rules.iter().enumerate() {
+            let field = |name: &str| format!("l_system.rules[{}].{}", index, name);
+            check_range(field("probability"), rule.probability as f64, 0.0, 1.0, &mut violations);
+            check_range(field("successor"), rule.successor.0.len() as f64, 0.0, max_modules, &mut violations);
+        }
+
+        let ordering_rules = [
+            ("trunk.split_height", "trunk.height", "split height must not exceed the trunk height"),
+            ("branching.angle_min", "branching.angle_max", "minimum branch angle must not exceed the maximum"),
+            ("branching.bend_angle_min", "branching.bend_angle_max", "minimum bend angle must not exceed the maximum"),
+            ("branching.frequency_min", "branching.frequency_max", "minimum branch frequency must not exceed the maximum"),
+            ("space_colonization.kill_radius", "space_colonization.influence_radius", "kill radius must not exceed the influence radius"),
+        ];
+        for (lower, upper, reason) in ordering_rules {
+            self.check_ordering(lower, upper, reason, &mut violations);
+        }
+
+        violations
+    }
+
+    fn check_ordering(
+        &self,
+        lower: &'static str,
+        upper: &'static str,
+        reason: &str,
+        violations: &mut Vec<ParamViolation>,
+    ) {
+        let (Some(lower_value), Some(upper_value)) = (self.get(lower), self.get(upper)) else {
+            return;
+        };
+        let (lower_value, upper_value) = (lower_value.as_f64(), upper_value.as_f64());
+        if lower_value > upper_value {
+            let min = ParamSpec::find(lower).map_or(f64::MIN, |spec| spec.min);
+            violations.push(ParamViolation {
+                field: lower.to_string(),
+                value: lower_value,
+                min,
+                max: upper_value,
+                reason: ViolationReason::Ordering,
+                message: format!("{} = {}: {} ({} = {})", lower, lower_value, reason, upper, upper_value),
+            });
+        }
+    }
+
+    /// Validate, turning any violations into a `ParameterError`.
+    pub fn validated(self) -> Result<Self, ParameterError> {
+        let violations = self.validate();
+        if violations.is_empty() {
+            Ok(self)
+        } else {
+            Err(ParameterError::validation(violations))
+        }
+    }
+}
+
+/// Record a violation if `value` is not a finite number within `min..=max`.
+fn check_range(field: String, value: f64, min: f64, max: f64, violations: &mut Vec<ParamViolation>) {
+    if !value.is_finite() {
+        violations.push(ParamViolation {
+            message: format!("{} must be a finite number", field),
+            field,
+            value,
+            min,
+            max,
+            reason: ViolationReason::NotANumber,
+        });
+    } else if value < min || value > max {
+        violations.push(ParamViolation {
+            message: format!("{} = {} is outside {}..={}", field, value, min, max),
+            field,
+            value,
+            min,
+            max,
+            reason: ViolationReason::OutOfRange,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{GuideCurve, Modules};
+    use glam::Vec3;
+
+    #[test]
+    fn default_parameters_validate() {
+        assert_eq!(TreeParameters::default().validate(), Vec::new());
+    }
+
+    #[test]
+    fn guides_need_the_random_walk_model() {
+        let mut params = TreeParameters::default();
+        params.guides.branches.push(GuideCurve::new(vec![Vec3::Y, Vec3::new(2.0, 3.0, 0.0)], Default::default()));
+        assert!(params.validate().is_empty());
+
+        params.branching.model = BranchingModel::Growth;
+        let violations = params.validate();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "guides");
+        assert_eq!(violations[0].reason, ViolationReason::Unsupported);
+    }
+
+    #[test]
+    fn grammar_lengths_are_limited() {
+        let mut params = TreeParameters::default();
+        params.l_system.rules[0].successor = Modules::parse(&"F".repeat(MAX_GRAMMAR_MODULES + 1)).unwrap();
+        let fields: Vec<String> = params.validate().into_iter().map(|violation| violation.field).collect();
+        assert_eq!(fields, vec!["l_system.rules[0].successor".to_string()]);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::core::{BranchGenerator, GenerationContext, SkeletonEdit, sub_seed};
+use crate::structure::TreeStructure;
+use glam::{Quat, Vec3};
+use rand::{RngCore, SeedableRng, rngs::SmallRng};
+
+/// Replay manual skeleton edits in order on top of the procedural skeleton.
+/// Each edit's anchor is looked up on the tree left by the edits before it;
+/// edits whose branch is gone or too short, or that would prune the root,
+/// are skipped and listed in `tree.skipped_edits`. Grafted limbs are grown
+/// by `branching`, each from its own RNG, so adding an edit never reshapes
+/// the limbs grafted before it. The branch hierarchy is left up to date for
+/// the edited skeleton, and `tree.skeleton_indices` starts tracking its
+/// cross-sections through the later stages.
+pub fn apply_edits(
+    edits: &[SkeletonEdit],
+    branching: &dyn BranchGenerator,
+    context: &GenerationContext,
+    tree: &mut TreeStructure,
+    rng: &mut SmallRng,
+) {
+    let stream_seed = rng.next_u64();
+    tree.skipped_edits.clear();
+    tree.build_hierarchy();
+
+    for (index, edit) in edits.iter().enumerate() {
+        let (branch, distance) = edit.anchor();
+        let root_prune = |anchor: &usize| *anchor == 0 && matches!(edit, SkeletonEdit::Prune { .. });
+        let Some(anchor) = tree.cross_section_along_branch(branch, distance).filter(|anchor| !root_prune(anchor)) else {
+            tree.skipped_edits.push(index);
+            continue;
+        };
+
+        match edit {
+            SkeletonEdit::Prune { .. } => tree.remove_subtrees(&[anchor]),
+            SkeletonEdit::Graft { direction, .. } => {
+                let mut edit_rng = SmallRng::seed_from_u64(sub_seed(stream_seed, index as u64));
+                branching.graft(tree, anchor, *direction, context, &mut edit_rng);
+            }
+            SkeletonEdit::Move { offset, .. } => {
+                tree.transform_subtree(anchor, Vec3::ZERO, Quat::IDENTITY, *offset);
+            }
+            SkeletonEdit::Rotate { axis, angle, .. } => {
+                // The limb pivots where it leaves its parent
+                let base = tree.cross_sections[anchor].parent_index.unwrap_or(anchor);
+                let pivot = tree.cross_sections[base].center;
+                let rotation = Quat::from_axis_angle(axis.normalize_or(Vec3::Y), angle.to_radians());
+                tree.transform_subtree(anchor, pivot, rotation, Vec3::ZERO);
+            }
+        }
+        tree.build_hierarchy();
+    }
+    tree.skeleton_indices = (0..tree.cross_sections.len()).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{
+        BranchGenerator, BranchingParams, GenerationContext, SkeletonEdit, TreeParameters, TreeSubsystem,
+        generator::ModularTreeGenerator,
+    };
+    use crate::structure::{RingType, TreeStructure};
+    use glam::Vec3;
+    use rand::rngs::SmallRng;
+
+    /// Branching that grows nothing and grafts a single cross-section
+    struct SingleSectionGrafts;
+
+    impl TreeSubsystem for SingleSectionGrafts {
+        type Params = BranchingParams;
+        type Output = ();
+
+        fn generate(&self, _: &BranchingParams, _: &GenerationContext, _: &mut TreeStructure, _: &mut SmallRng) {}
+    }
+
+    impl BranchGenerator for SingleSectionGrafts {
+        fn graft(&self, tree: &mut TreeStructure, parent: usize, direction: Vec3, _: &GenerationContext, _: &mut SmallRng) {
+            let mut cross_section = tree.cross_sections[parent].clone();
+            cross_section.center += direction;
+            cross_section.children_indices.clear();
+            cross_section.parent_index = Some(parent);
+            let index = tree.cross_sections.len();
+            tree.cross_sections[parent].children_indices.push(index);
+            tree.cross_sections.push(cross_section);
+        }
+    }
+
+    fn generate(params: &TreeParameters) -> TreeStructure {
+        ModularTreeGenerator::new().generate_tree(params)
+    }
+
+    fn crown_centers(tree: &TreeStructure) -> Vec<Vec3> {
+        tree.cross_sections
+            .iter()
+            .filter(|cross_section| !cross_section.component_rings.iter().any(|ring| matches!(ring.ring_type, RingType::Root { .. })))
+            .map(|cross_section| cross_section.center)
+            .collect()
+    }
+
+    #[test]
+    fn edits_stay_on_their_branch_when_roots_change() {
+        let mut params = TreeParameters::default();
+        params.roots.enable = false;
+        let unedited = generate(&params);
+        let branch = unedited.branches.len() / 2;
+        let distance = unedited.branches[branch].length * 0.5;
+        params.edits.push(SkeletonEdit::Prune { branch, distance });
+
+        let pruned = generate(&params);
+        params.roots.enable = true;
+        let pruned_with_roots = generate(&params);
+
+        assert!(pruned.skipped_edits.is_empty());
+        assert!(pruned_with_roots.skipped_edits.is_empty());
+        assert!(pruned.cross_sections.len() < unedited.cross_sections.len());
+        assert_eq!(crown_centers(&pruned), crown_centers(&pruned_with_roots));
+    }
+
+    #[test]
+    fn edits_whose_anchor_is_gone_are_skipped() {
+        let mut params = TreeParameters::default();
+        let unedited = generate(&params);
+        let trunk_length = unedited.branches[0].length;
+        params.edits = vec![
+            SkeletonEdit::Prune { branch: unedited.branches.len(), distance: 0.0 },
+            SkeletonEdit::Prune { branch: 0, distance: trunk_length + 1.0 },
+            SkeletonEdit::Prune { branch: 0, distance: 0.0 },
+        ];
+
+        let tree = generate(&params);
+        assert_eq!(tree.skipped_edits, vec![0, 1, 2]);
+        assert_eq!(tree.cross_sections.len(), unedited.cross_sections.len());
+    }
+
+    #[test]
+    fn grafts_are_grown_by_the_generator_branching_system() {
+        let generator = ModularTreeGenerator::builder().branching(SingleSectionGrafts).build();
+        let mut params = TreeParameters::default();
+        params.roots.enable = false;
+        let unedited = generator.generate_tree(&params);
+
+        params.edits.push(SkeletonEdit::Graft { branch: 0, distance: 0.0, direction: Vec3::X });
+        let tree = generator.generate_tree(&params);
+        assert_eq!(tree.cross_sections.len(), unedited.cross_sections.len() + 1);
+        assert_eq!(tree.cross_sections.last().unwrap().center, tree.cross_sections[0].center + Vec3::X);
+    }
+}
@@ -2,17 +2,21 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
 use std::collections::BTreeMap;
 
-// New modular structure
-mod core;
-mod trunk;
-mod branching;
-mod roots;
-mod twigs;
-mod structure;
+// New modular structure. The generation modules are public so other crates can
+// plug their own subsystems into `ModularTreeGenerator`.
+pub mod core;
+pub mod trunk;
+pub mod branching;
+pub mod roots;
+pub mod twigs;
+pub mod modifiers;
+pub mod editing;
+pub mod structure;
 mod wasm;
 
 
-use core::{TreeParameters, generator::ModularTreeGenerator};
+use core::{BranchingModel, ChangeKind, Curve, DepthCurves, EnvelopeMode, EnvelopeShape, GuideCurve, GuideInterpolation, LSystemParams, Occluder, SkeletonEdit, Phyllotaxis, RadiusModel, TreeParameters, ParamViolation, ParameterDiff, ParameterError, ParameterHistory};
+use core::generator::{GenerationCache, GenerationStage, ModularTreeGenerator};
 use structure::TwigType;
 
 #[wasm_bindgen]
@@ -20,6 +24,10 @@ pub struct TreeObject {
     params: TreeParameters,
     tree: structure::TreeStructure,
     generator: ModularTreeGenerator,
+    cache: GenerationCache,
+    update_depth: u32,        // Open begin_update() calls; regeneration is deferred while > 0
+    dirty_stage: Option<GenerationStage>, // Earliest pipeline stage invalidated since the last regeneration
+    history: ParameterHistory, // Snapshots for undo and redo
 }
 
 #[wasm_bindgen]
@@ -29,6 +37,7 @@ pub struct TreeMesh {
     uvs: Vec<f32>,
     indices: Vec<u32>,
     depths: Vec<u32>, // Depth value for each vertex
+    branch_ids: Vec<u32>, // Branch id for each vertex
 }
 
 #[wasm_bindgen]
@@ -57,6 +66,11 @@ impl TreeMesh {
     pub fn depths(&self) -> Vec<u32> {
         self.depths.clone()
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn branch_ids(&self) -> Vec<u32> {
+        self.branch_ids.clone()
+    }
 }
 
 #[wasm_bindgen]
@@ -68,6 +82,7 @@ impl TreeObject {
         let general_params = GeneralParams {
             seed,
             max_depth: 8,
+            ..GeneralParams::default()
         };
         
         let trunk_params = TrunkParams {
@@ -90,6 +105,7 @@ impl TreeObject {
             radius_taper: 0.8,
             azimuth_variation: 0.8,
             max_reach: 25.0,
+            ..BranchingParams::default()
         };
         
         let root_params = RootParams {
@@ -113,20 +129,282 @@ impl TreeObject {
             branching: branching_params,
             roots: root_params,
             twigs: twig_params,
+            ..TreeParameters::default()
         };
         
-        let generator = ModularTreeGenerator::new();
-        let tree = generator.generate_tree(&params);
-        
-        Ok(TreeObject {
+        let params = params.validated().map_err(parameter_error)?;
+        Ok(TreeObject::from_params(params))
+    }
+
+    /// Create a tree from a parameter file previously produced by `params_json`.
+    /// Missing fields fall back to their defaults; malformed JSON, unknown fields
+    /// and invalid values are returned as a structured error (see `ParameterError`).
+    pub fn from_params_json(json: &str) -> Result<TreeObject, JsValue> {
+        let params = TreeParameters::from_json(json)
+            .map_err(|e| parameter_error(ParameterError::parse(e)))?
+            .validated()
+            .map_err(parameter_error)?;
+        Ok(TreeObject::from_params(params))
+    }
+
+    /// Create a tree from one of the built-in species presets (see `preset_names`).
+    pub fn from_preset(name: &str, seed: u32) -> Result<TreeObject, JsValue> {
+        let mut params = TreeParameters::preset(name)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown tree preset: {}", name)))?;
+        params.general.seed = seed;
+        let params = params.validated().map_err(parameter_error)?;
+        Ok(TreeObject::from_params(params))
+    }
+
+    /// Serialize the full parameter set as JSON so the tree can be saved and reloaded exactly.
+    pub fn params_json(&self) -> Result<String, JsValue> {
+        self.params.to_json()
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize tree parameters: {}", e)))
+    }
+
+    fn from_params(params: TreeParameters) -> TreeObject {
+        TreeObject::from_params_with_generator(params, ModularTreeGenerator::new())
+    }
+
+    fn from_params_with_generator(params: TreeParameters, generator: ModularTreeGenerator) -> TreeObject {
+        let mut cache = GenerationCache::default();
+        let tree = generator.regenerate(&params, GenerationStage::Trunk, &mut cache);
+
+        TreeObject {
+            history: ParameterHistory::new(params.clone()),
             params,
             tree,
             generator,
-        })
+            cache,
+            update_depth: 0,
+            dirty_stage: None,
+        }
     }
     
+    /// Rerun the pipeline from the earliest stage invalidated since the last
+    /// regeneration. Does nothing while a batch update is open.
     fn regenerate_tree(&mut self) {
-        self.tree = self.generator.generate_tree(&self.params);
+        if self.update_depth > 0 {
+            return;
+        }
+        if let Some(stage) = self.dirty_stage.take() {
+            self.tree = self.generator.regenerate(&self.params, stage, &mut self.cache);
+        }
+    }
+
+    /// Replace the parameters and record the change in the history. Changes
+    /// made inside a batch update become a single history step.
+    fn set_params(&mut self, params: TreeParameters) {
+        let diff = ParameterDiff::new(&self.params, &params);
+        let change = diff.change();
+        if change != ChangeKind::Fields(Vec::new()) {
+            let change = if self.update_depth > 0 { ChangeKind::Batch } else { change };
+            self.history.record(params.clone(), change);
+        }
+        self.replace_params(params, diff.first_affected());
+    }
+
+    /// Replace the parameters without touching the history.
+    fn restore_params(&mut self, params: TreeParameters) {
+        let stage = GenerationStage::first_affected(&self.params, &params);
+        self.replace_params(params, stage);
+    }
+
+    /// Replace the parameters, invalidating only the pipeline stages from
+    /// `stage` on, the first one reading a group that actually changed.
+    fn replace_params(&mut self, params: TreeParameters, stage: Option<GenerationStage>) {
+        if let Some(stage) = stage {
+            self.dirty_stage = Some(self.dirty_stage.map_or(stage, |dirty| dirty.min(stage)));
+        }
+        self.params = params;
+        self.regenerate_tree();
+    }
+
+    /// Start a batch of parameter changes. Setters called before the matching
+    /// `commit_update` only record their value; the tree is regenerated once on commit.
+    pub fn begin_update(&mut self) {
+        if self.update_depth == 0 {
+            self.history.seal();
+        }
+        self.update_depth += 1;
+    }
+
+    /// Finish a batch started with `begin_update`, regenerating the tree if anything changed.
+    pub fn commit_update(&mut self) {
+        self.update_depth = self.update_depth.saturating_sub(1);
+        if self.update_depth == 0 {
+            self.history.seal();
+        }
+        self.regenerate_tree();
+    }
+
+    // History. Every parameter change, skeleton edits included, is a step;
+    // repeated changes to the same parameters merge into one step until
+    // `end_gesture` is called, so front ends must call it when a slider is
+    // released.
+
+    /// Go back one step. Returns false when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo().cloned() {
+            Some(params) => {
+                self.restore_params(params);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redo the last undone step. Returns false when there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo().cloned() {
+            Some(params) => {
+                self.restore_params(params);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Close the current history step so the next change to the same
+    /// parameter can be undone on its own. Call it whenever a slider is
+    /// released (dat.GUI's `onFinishChange`); without it, separate drags of
+    /// the same slider merge into a single step.
+    pub fn end_gesture(&mut self) {
+        self.history.seal();
+    }
+
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn history_position(&self) -> usize {
+        self.history.position()
+    }
+
+    /// Restore any step of the history directly; only the target tree is generated
+    pub fn jump_to_history(&mut self, index: usize) -> Result<(), JsValue> {
+        let params = self.history.jump_to(index).cloned()
+            .ok_or_else(|| JsValue::from_str(&format!("No history step at index {}", index)))?;
+        self.restore_params(params);
+        Ok(())
+    }
+
+    /// Apply a partial parameter JSON (same layout as `params_json`) in one step.
+    /// All changes are validated before any of them is applied, and the tree is
+    /// regenerated exactly once. Errors are reported like `from_params_json`.
+    pub fn apply_params(&mut self, json: &str) -> Result<(), JsValue> {
+        let params = self.params.with_json_patch(json)
+            .map_err(|e| parameter_error(ParameterError::parse(e)))?
+            .validated()
+            .map_err(parameter_error)?;
+
+        self.set_params(params);
+        Ok(())
+    }
+
+    /// Toggle one entry of the modifier stack without removing it.
+    pub fn set_modifier_enabled(&mut self, index: usize, enabled: bool) -> Result<(), JsValue> {
+        if index >= self.params.modifiers.len() {
+            return Err(JsValue::from_str(&format!("No modifier at index {}", index)));
+        }
+        self.update_params(|params| params.modifiers[index].enabled = enabled)
+    }
+
+    // Skeleton editing. Edits are stored with the parameters and replayed on
+    // every regeneration. They take the cross-section indices reported by the
+    // ring accessors and store them as a branch id and a distance along that
+    // branch of the edited skeleton, before any modifier pruned it.
+
+    /// Remove a cross-section and everything growing from it
+    pub fn prune_branch(&mut self, index: usize) -> Result<(), JsValue> {
+        self.push_edit(index, |branch, distance| SkeletonEdit::Prune { branch, distance })
+    }
+
+    /// Grow a new limb from a cross-section in the given direction
+    pub fn graft_branch(&mut self, index: usize, x: f32, y: f32, z: f32) -> Result<(), JsValue> {
+        let direction = glam::Vec3::new(x, y, z);
+        self.push_edit(index, |branch, distance| SkeletonEdit::Graft { branch, distance, direction })
+    }
+
+    /// Shift the limb starting at a cross-section
+    pub fn move_branch(&mut self, index: usize, x: f32, y: f32, z: f32) -> Result<(), JsValue> {
+        let offset = glam::Vec3::new(x, y, z);
+        self.push_edit(index, |branch, distance| SkeletonEdit::Move { branch, distance, offset })
+    }
+
+    /// Swing the limb starting at a cross-section around its base by `angle` degrees
+    pub fn rotate_branch(&mut self, index: usize, axis_x: f32, axis_y: f32, axis_z: f32, angle: f32) -> Result<(), JsValue> {
+        let axis = glam::Vec3::new(axis_x, axis_y, axis_z);
+        self.push_edit(index, |branch, distance| SkeletonEdit::Rotate { branch, distance, axis, angle })
+    }
+
+    pub fn edits_count(&self) -> usize {
+        self.params.edits.len()
+    }
+
+    pub fn remove_edit(&mut self, index: usize) -> Result<(), JsValue> {
+        if index >= self.params.edits.len() {
+            return Err(JsValue::from_str(&format!("No edit at index {}", index)));
+        }
+        self.update_params(|params| {
+            params.edits.remove(index);
+        })
+    }
+
+    pub fn clear_edits(&mut self) -> Result<(), JsValue> {
+        self.update_params(|params| params.edits.clear())
+    }
+
+    /// Edits skipped in the last regeneration because their branch is gone or
+    /// no longer reaches their anchor
+    pub fn skipped_edits(&self) -> Vec<usize> {
+        self.tree.skipped_edits.clone()
+    }
+
+    /// Add an edit anchored to cross-section `index` of `self.tree`. The anchor
+    /// is looked up on the skeleton the edit will be replayed on, the cached
+    /// input of the Twigs stage, since the modifiers may have removed limbs
+    /// and renumbered the rest since. Edits can't be added during a batch
+    /// update, when that skeleton is out of date.
+    fn push_edit(&mut self, index: usize, edit: impl FnOnce(usize, f32) -> SkeletonEdit) -> Result<(), JsValue> {
+        if self.update_depth > 0 {
+            return Err(JsValue::from_str("Skeleton edits can't be added during a batch update"));
+        }
+        let skeleton = self
+            .cache
+            .stage_input(GenerationStage::Twigs)
+            .ok_or_else(|| JsValue::from_str("The tree has not been generated yet"))?;
+        let anchor = self.tree.skeleton_indices.get(index).copied();
+        let Some((anchor, distance)) = anchor.and_then(|anchor| Some((anchor, skeleton.distance_along_branch(anchor)?))) else {
+            return Err(JsValue::from_str(&format!("No cross-section at index {}", index)));
+        };
+        let edit = edit(skeleton.cross_sections[anchor].branch_id, distance);
+        self.update_params(|params| params.edits.push(edit))
+    }
+
+    /// Violations of the current parameters, as an array of
+    /// `{ field, value, min, max, reason, message }` objects (empty when valid).
+    pub fn validate(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.params.validate())
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize violations: {}", e)))
+    }
+
+    /// Apply a single change through validation. Invalid values leave the
+    /// parameters untouched and are reported as a structured error.
+    fn update_params(&mut self, change: impl FnOnce(&mut TreeParameters)) -> Result<(), JsValue> {
+        let mut params = self.params.clone();
+        change(&mut params);
+        let params = params.validated().map_err(parameter_error)?;
+        self.set_params(params);
+        Ok(())
     }
 
     pub fn render(&mut self) {
@@ -152,6 +430,59 @@ impl TreeObject {
     pub fn twigs_count(&self) -> usize {
         self.tree.twigs.len()
     }
+
+    /// Parent cross-section of a cross-section (`None` for the root)
+    pub fn ring_parent(&self, index: usize) -> Option<usize> {
+        self.tree.cross_sections.get(index).and_then(|cross_section| cross_section.parent_index)
+    }
+
+    /// Branch a cross-section belongs to
+    pub fn ring_branch(&self, index: usize) -> Option<usize> {
+        self.tree.cross_sections.get(index).map(|cross_section| cross_section.branch_id)
+    }
+
+    pub fn branches_count(&self) -> usize {
+        self.tree.branches.len()
+    }
+
+    /// Branch a branch grows from (`None` for the trunk)
+    pub fn branch_parent(&self, id: usize) -> Option<usize> {
+        self.tree.branches.get(id).and_then(|branch| branch.parent_branch)
+    }
+
+    /// Branch order: 0 for the trunk, 1 for limbs growing from it, and so on
+    pub fn branch_order(&self, id: usize) -> Option<u32> {
+        self.tree.branches.get(id).map(|branch| branch.order)
+    }
+
+    pub fn branch_length(&self, id: usize) -> Option<f32> {
+        self.tree.branches.get(id).map(|branch| branch.length)
+    }
+
+    pub fn branch_base_radius(&self, id: usize) -> Option<f32> {
+        self.tree.branches.get(id).map(|branch| branch.base_radius)
+    }
+
+    /// Cross-sections of a branch, from its base to its tip
+    pub fn branch_rings(&self, id: usize) -> Vec<u32> {
+        self.tree.branch_members(id).iter().map(|&index| index as u32).collect()
+    }
+
+    /// Branches growing directly from a branch
+    pub fn branch_children(&self, id: usize) -> Vec<u32> {
+        self.tree
+            .branches
+            .iter()
+            .filter(|branch| branch.parent_branch == Some(id))
+            .map(|branch| branch.id as u32)
+            .collect()
+    }
+
+    /// Whether growth stopped early because the tree reached
+    /// `general.max_cross_sections`
+    pub fn budget_exhausted(&self) -> bool {
+        self.tree.budget_exhausted
+    }
     
     pub fn twig_position(&self, index: usize) -> Option<wasm::Vector3d> {
         self.tree.twigs.get(index).map(|twig| {
@@ -221,129 +552,399 @@ impl TreeObject {
             uvs,
             indices: ring_mesh.indices,
             depths: ring_mesh.depths,
+            branch_ids: ring_mesh.branch_ids,
         }
     }
 
-    pub fn set_trunk_height(&mut self, height: f32) {
-        self.params.trunk.height = height;
-        self.regenerate_tree();
+    pub fn set_trunk_height(&mut self, height: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.trunk.height = height)
     }
 
-    pub fn set_butressing(&mut self, butressing: f32) {
-        self.params.trunk.buttressing = butressing;
-        self.regenerate_tree();
+    pub fn set_butressing(&mut self, butressing: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.trunk.buttressing = butressing)
     }
 
-    pub fn set_split_height(&mut self, split_height: f32) {
-        self.params.trunk.split_height = split_height;
-        self.regenerate_tree();
+    pub fn set_split_height(&mut self, split_height: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.trunk.split_height = split_height)
     }
 
-    pub fn set_segment_length(&mut self, segment_length: f32) {
-        self.params.trunk.segment_length = segment_length.max(0.01);
-        self.regenerate_tree();
+    pub fn set_segment_length(&mut self, segment_length: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.trunk.segment_length = segment_length)
     }
 
-    pub fn set_branch_angle_range(&mut self, min: f32, max: f32) {
-        self.params.branching.angle_min = min.min(max);
-        self.params.branching.angle_max = max.max(min);
-        self.regenerate_tree();
+    pub fn set_branch_angle_range(&mut self, min: f32, max: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.branching.angle_min = min;
+            params.branching.angle_max = max;
+        })
     }
 
-    pub fn set_bend_angle_range(&mut self, min: f32, max: f32) {
-        self.params.branching.bend_angle_min = min.min(max);
-        self.params.branching.bend_angle_max = max.max(min);
-        self.regenerate_tree();
+    pub fn set_bend_angle_range(&mut self, min: f32, max: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.branching.bend_angle_min = min;
+            params.branching.bend_angle_max = max;
+        })
     }
 
-    pub fn set_branch_frequency_range(&mut self, min: u32, max: u32) {
-        let validated_min = min.max(1).min(max.max(1));
-        let validated_max = max.max(1).max(validated_min);
-        self.params.branching.frequency_min = validated_min;
-        self.params.branching.frequency_max = validated_max;
-        self.regenerate_tree();
+    pub fn set_branch_frequency_range(&mut self, min: u32, max: u32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.branching.frequency_min = min;
+            params.branching.frequency_max = max;
+        })
     }
 
-    pub fn set_max_depth(&mut self, max_depth: u32) {
-        self.params.general.max_depth = max_depth.max(1);
-        self.regenerate_tree();
+    pub fn set_max_depth(&mut self, max_depth: u32) -> Result<(), JsValue> {
+        self.update_params(|params| params.general.max_depth = max_depth)
     }
 
-    pub fn set_radius_taper(&mut self, radius_taper: f32) {
-        self.params.branching.radius_taper = radius_taper.max(0.1).min(1.0);
-        self.regenerate_tree();
+    pub fn set_max_cross_sections(&mut self, budget: u32) -> Result<(), JsValue> {
+        self.update_params(|params| params.general.max_cross_sections = budget)
     }
 
-    pub fn set_trunk_ring_spread(&mut self, trunk_ring_spread: f32) {
-        self.params.trunk.ring_spread = trunk_ring_spread.max(0.0).min(2.0);
-        self.regenerate_tree();
+    pub fn set_radius_taper(&mut self, radius_taper: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.branching.radius_taper = radius_taper)
     }
 
-    pub fn set_segment_length_variation(&mut self, segment_length_variation: f32) {
-        self.params.trunk.segment_length_variation = segment_length_variation.max(0.0).min(1.0);
-        self.regenerate_tree();
+    pub fn set_trunk_ring_spread(&mut self, trunk_ring_spread: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.trunk.ring_spread = trunk_ring_spread)
     }
 
-    pub fn set_trunk_size(&mut self, trunk_size: f32) {
-        self.params.trunk.size = trunk_size.max(0.1).min(10.0);
-        self.regenerate_tree();
+    pub fn set_segment_length_variation(&mut self, segment_length_variation: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.trunk.segment_length_variation = segment_length_variation)
     }
 
-    pub fn set_branch_azimuth_variation(&mut self, variation: f32) {
-        self.params.branching.azimuth_variation = variation.max(0.0).min(1.0);
-        self.regenerate_tree();
+    pub fn set_trunk_size(&mut self, trunk_size: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.trunk.size = trunk_size)
     }
 
-    pub fn set_max_branch_reach(&mut self, reach: f32) {
-        self.params.branching.max_reach = reach.max(2.0).min(50.0);
-        self.regenerate_tree();
+    pub fn set_branch_azimuth_variation(&mut self, variation: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.branching.azimuth_variation = variation)
+    }
+
+    pub fn set_max_branch_reach(&mut self, reach: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.branching.max_reach = reach)
+    }
+
+    /// Select the growth algorithm by name ("random_walk", "space_colonization",
+    /// "l_system" or "growth")
+    pub fn set_branching_model(&mut self, model: &str) -> Result<(), JsValue> {
+        let model: BranchingModel = serde_json::from_value(serde_json::Value::from(model))
+            .map_err(|e| parameter_error(ParameterError::parse(e)))?;
+        self.update_params(|params| params.branching.model = model)
+    }
+
+    /// Select the side-branch arrangement by name ("random", "alternate",
+    /// "opposite", "decussate", "whorled" or "spiral")
+    pub fn set_phyllotaxis(&mut self, phyllotaxis: &str) -> Result<(), JsValue> {
+        let phyllotaxis: Phyllotaxis = serde_json::from_value(serde_json::Value::from(phyllotaxis))
+            .map_err(|e| parameter_error(ParameterError::parse(e)))?;
+        self.update_params(|params| params.branching.phyllotaxis = phyllotaxis)
+    }
+
+    pub fn set_whorl_count(&mut self, count: u32) -> Result<(), JsValue> {
+        self.update_params(|params| params.branching.whorl_count = count)
+    }
+
+    pub fn set_divergence_angle(&mut self, angle: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.branching.divergence_angle = angle)
+    }
+
+    pub fn set_codominant_forks(&mut self, probability: f32, count: u32, angle: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.branching.codominant_probability = probability;
+            params.branching.codominant_count = count;
+            params.branching.codominant_angle = angle;
+        })
+    }
+
+    /// Select how forks divide their radius by name ("fixed" or "pipe_model")
+    pub fn set_radius_model(&mut self, model: &str) -> Result<(), JsValue> {
+        let model: RadiusModel = serde_json::from_value(serde_json::Value::from(model))
+            .map_err(|e| parameter_error(ParameterError::parse(e)))?;
+        self.update_params(|params| params.branching.radius_model = model)
+    }
+
+    pub fn set_pipe_model(&mut self, exponent: f32, lateral_importance: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.branching.pipe_exponent = exponent;
+            params.branching.lateral_importance = lateral_importance;
+        })
+    }
+
+    /// Replace one of the depth curves ("angle", "bend", "length", "taper",
+    /// "frequency" or "azimuth_spread") with control points given as a flat
+    /// list of x, y pairs
+    pub fn set_depth_curve(&mut self, name: &str, points: Vec<f32>) -> Result<(), JsValue> {
+        Self::check_depth_curve_name(name)?;
+        if !points.len().is_multiple_of(2) {
+            let message = format!("Depth curve \"{}\" needs x, y pairs but got {} numbers", name, points.len());
+            return Err(Self::depth_curve_error(name, points.len(), message));
+        }
+        let curve = Curve(points.chunks_exact(2).map(|pair| [pair[0], pair[1]]).collect());
+        self.update_params(|params| {
+            if let Some(target) = params.branching.curves.curve_mut(name) {
+                *target = Some(curve);
+            }
+        })
+    }
+
+    /// Unset a depth curve so its level follows the built-in per-depth behaviour again
+    pub fn clear_depth_curve(&mut self, name: &str) -> Result<(), JsValue> {
+        Self::check_depth_curve_name(name)?;
+        self.update_params(|params| {
+            if let Some(target) = params.branching.curves.curve_mut(name) {
+                *target = None;
+            }
+        })
+    }
+
+    /// Control points of a depth curve as a flat list of x, y pairs (`None`
+    /// while the curve is unset)
+    pub fn depth_curve(&self, name: &str) -> Option<Vec<f32>> {
+        self.params.branching.curves.curve(name).map(|curve| curve.0.iter().flatten().copied().collect())
+    }
+
+    fn check_depth_curve_name(name: &str) -> Result<(), JsValue> {
+        if DepthCurves::NAMES.contains(&name) {
+            return Ok(());
+        }
+        let message = format!("Unknown depth curve \"{}\"; expected one of {}", name, DepthCurves::NAMES.join(", "));
+        Err(Self::depth_curve_error(name, 0, message))
+    }
+
+    /// Validation error for the depth curve field `name`
+    fn depth_curve_error(name: &str, value: usize, message: String) -> JsValue {
+        let field = format!("branching.curves.{}", name);
+        parameter_error(ParameterError::validation(vec![ParamViolation::malformed(field, value as f64, message)]))
+    }
+
+    // Growth simulation setters
+    /// Grow the tree for `age` years; the same seed at a lower age gives the
+    /// younger version of the same tree
+    pub fn set_age(&mut self, age: u32) -> Result<(), JsValue> {
+        self.update_params(|params| params.growth.age = age)
+    }
+
+    pub fn set_shoot_growth(&mut self, shoot_length: f32, lateral_vigor: f32, vigor_decline: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.growth.shoot_length = shoot_length;
+            params.growth.lateral_vigor = lateral_vigor;
+            params.growth.vigor_decline = vigor_decline;
+        })
+    }
+
+    pub fn set_bud_break(&mut self, buds_per_shoot: u32, probability: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.growth.buds_per_shoot = buds_per_shoot;
+            params.growth.bud_break = probability;
+        })
+    }
+
+    pub fn set_ring_width(&mut self, width: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.growth.ring_width = width)
+    }
+
+    // Light competition setters
+    pub fn set_light_competition(&mut self, enabled: bool) -> Result<(), JsValue> {
+        self.update_params(|params| params.light.enabled = enabled)
+    }
+
+    pub fn set_shadow_grid(&mut self, voxel_size: f32, shadow_strength: f32, shadow_depth: u32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.light.voxel_size = voxel_size;
+            params.light.shadow_strength = shadow_strength;
+            params.light.shadow_depth = shadow_depth;
+        })
+    }
+
+    pub fn set_light_response(&mut self, response: f32, shade_tolerance: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.light.response = response;
+            params.light.shade_tolerance = shade_tolerance;
+        })
+    }
+
+    /// Add a box that shades the tree, such as a wall or a neighbouring tree
+    pub fn add_occluder(&mut self, min_x: f32, min_y: f32, min_z: f32, max_x: f32, max_y: f32, max_z: f32) -> Result<(), JsValue> {
+        let occluder = Occluder {
+            min: glam::Vec3::new(min_x, min_y, min_z),
+            max: glam::Vec3::new(max_x, max_y, max_z),
+        };
+        self.update_params(|params| params.light.occluders.push(occluder))
+    }
+
+    pub fn clear_occluders(&mut self) -> Result<(), JsValue> {
+        self.update_params(|params| params.light.occluders.clear())
+    }
+
+    // Guide curve setters
+    /// Make the trunk follow a guide through `points`, a flat list of x, y, z
+    /// triples, joined by "polyline" or "spline" interpolation
+    pub fn set_trunk_guide(&mut self, points: Vec<f32>, interpolation: &str) -> Result<(), JsValue> {
+        let guide = parse_guide(points, interpolation)?;
+        self.update_params(|params| params.guides.trunk = Some(guide))
+    }
+
+    pub fn clear_trunk_guide(&mut self) -> Result<(), JsValue> {
+        self.update_params(|params| params.guides.trunk = None)
+    }
+
+    /// Add a first-order limb following a guide, given like `set_trunk_guide`
+    pub fn add_branch_guide(&mut self, points: Vec<f32>, interpolation: &str) -> Result<(), JsValue> {
+        let guide = parse_guide(points, interpolation)?;
+        self.update_params(|params| params.guides.branches.push(guide))
+    }
+
+    pub fn clear_branch_guides(&mut self) -> Result<(), JsValue> {
+        self.update_params(|params| params.guides.branches.clear())
+    }
+
+    pub fn set_keep_procedural_limbs(&mut self, keep: bool) -> Result<(), JsValue> {
+        self.update_params(|params| params.guides.keep_procedural_limbs = keep)
+    }
+
+    /// Points of the trunk guide as a flat list of x, y, z triples
+    pub fn trunk_guide(&self) -> Option<Vec<f32>> {
+        self.params.guides.trunk.as_ref().map(|guide| guide.points.iter().flat_map(|point| point.to_array()).collect())
+    }
+
+    pub fn branch_guides_count(&self) -> usize {
+        self.params.guides.branches.len()
+    }
+
+    /// Points of a limb guide as a flat list of x, y, z triples
+    pub fn branch_guide(&self, index: usize) -> Option<Vec<f32>> {
+        self.params.guides.branches.get(index).map(|guide| guide.points.iter().flat_map(|point| point.to_array()).collect())
+    }
+
+    // Tropism setters
+    pub fn set_light_direction(&mut self, x: f32, y: f32, z: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.tropism.light_direction = glam::Vec3::new(x, y, z))
+    }
+
+    pub fn set_phototropism(&mut self, strength: f32, depth_scale: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.tropism.phototropism = strength;
+            params.tropism.phototropism_depth_scale = depth_scale;
+        })
+    }
+
+    pub fn set_gravitropism(&mut self, strength: f32, depth_scale: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.tropism.gravitropism = strength;
+            params.tropism.gravitropism_depth_scale = depth_scale;
+        })
+    }
+
+    /// Load an L-system grammar from its text form (see `LSystemParams::from_text`)
+    /// and switch the tree to the L-system branching model
+    pub fn load_l_system(&mut self, text: &str) -> Result<(), JsValue> {
+        let l_system = LSystemParams::from_text(text)
+            .map_err(|e| parameter_error(ParameterError::Parse { message: e.to_string() }))?;
+        self.update_params(|params| {
+            params.l_system = l_system;
+            params.branching.model = BranchingModel::LSystem;
+        })
+    }
+
+    /// The current L-system grammar in the text form read by `load_l_system`
+    pub fn l_system_text(&self) -> String {
+        self.params.l_system.to_text()
+    }
+
+    // Space colonization setters
+    pub fn set_attraction_points(&mut self, count: u32) -> Result<(), JsValue> {
+        self.update_params(|params| params.space_colonization.attraction_points = count)
+    }
+
+    pub fn set_crown_size(&mut self, radius: f32, height: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.space_colonization.crown_radius = radius;
+            params.space_colonization.crown_height = height;
+        })
+    }
+
+    pub fn set_colonization_radii(&mut self, influence: f32, kill: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.space_colonization.influence_radius = influence;
+            params.space_colonization.kill_radius = kill;
+        })
+    }
+
+    pub fn set_colonization_iterations(&mut self, iterations: u32) -> Result<(), JsValue> {
+        self.update_params(|params| params.space_colonization.iterations = iterations)
+    }
+
+    // Crown envelope setters
+    /// Select the crown shape by name ("none", "cone", "ellipsoid", "cylinder",
+    /// "inverted_cone", "flame" or "custom")
+    pub fn set_envelope_shape(&mut self, shape: &str) -> Result<(), JsValue> {
+        let shape: EnvelopeShape = serde_json::from_value(serde_json::Value::from(shape))
+            .map_err(|e| parameter_error(ParameterError::parse(e)))?;
+        self.update_params(|params| params.envelope.shape = shape)
+    }
+
+    /// Select what happens to branches leaving the crown ("prune" or "steer")
+    pub fn set_envelope_mode(&mut self, mode: &str) -> Result<(), JsValue> {
+        let mode: EnvelopeMode = serde_json::from_value(serde_json::Value::from(mode))
+            .map_err(|e| parameter_error(ParameterError::parse(e)))?;
+        self.update_params(|params| params.envelope.mode = mode)
+    }
+
+    pub fn set_envelope_size(&mut self, base: f32, height: f32, radius: f32) -> Result<(), JsValue> {
+        self.update_params(|params| {
+            params.envelope.base = base;
+            params.envelope.height = height;
+            params.envelope.radius = radius;
+        })
+    }
+
+    pub fn set_envelope_steer_strength(&mut self, strength: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.envelope.steer_strength = strength)
+    }
+
+    /// Set the custom crown profile: radius fractions sampled evenly from the
+    /// crown base to its top
+    pub fn set_envelope_profile(&mut self, profile: Vec<f32>) -> Result<(), JsValue> {
+        self.update_params(|params| params.envelope.profile = profile)
     }
 
     // Root system setters
-    pub fn set_root_enable(&mut self, enable: bool) {
-        self.params.roots.enable = enable;
-        self.regenerate_tree();
+    pub fn set_root_enable(&mut self, enable: bool) -> Result<(), JsValue> {
+        self.update_params(|params| params.roots.enable = enable)
     }
 
-    pub fn set_root_depth(&mut self, depth: f32) {
-        self.params.roots.depth = depth.max(0.5).min(3.0);
-        self.regenerate_tree();
+    pub fn set_root_depth(&mut self, depth: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.roots.depth = depth)
     }
 
-    pub fn set_root_spread(&mut self, spread: f32) {
-        self.params.roots.spread = spread.max(0.5).min(2.0);
-        self.regenerate_tree();
+    pub fn set_root_spread(&mut self, spread: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.roots.spread = spread)
     }
 
-    pub fn set_root_density(&mut self, density: u32) {
-        self.params.roots.density = density.max(2).min(8);
-        self.regenerate_tree();
+    pub fn set_root_density(&mut self, density: u32) -> Result<(), JsValue> {
+        self.update_params(|params| params.roots.density = density)
     }
 
-    pub fn set_root_segment_length(&mut self, segment_length: f32) {
-        self.params.roots.segment_length = segment_length.max(0.1).min(0.8);
-        self.regenerate_tree();
+    pub fn set_root_segment_length(&mut self, segment_length: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.roots.segment_length = segment_length)
     }
 
     // Twig system setters
-    pub fn set_twig_enable(&mut self, enable: bool) {
-        self.params.twigs.enable = enable;
-        self.regenerate_tree();
+    pub fn set_twig_enable(&mut self, enable: bool) -> Result<(), JsValue> {
+        self.update_params(|params| params.twigs.enable = enable)
     }
 
-    pub fn set_twig_density(&mut self, density: f32) {
-        self.params.twigs.density = density.max(0.1).min(2.0);
-        self.regenerate_tree();
+    pub fn set_twig_density(&mut self, density: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.twigs.density = density)
     }
 
-    pub fn set_twig_scale(&mut self, scale: f32) {
-        self.params.twigs.scale = scale.max(0.1).min(3.0);
-        self.regenerate_tree();
+    pub fn set_twig_scale(&mut self, scale: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.twigs.scale = scale)
     }
 
-    pub fn set_twig_angle_variation(&mut self, variation: f32) {
-        self.params.twigs.angle_variation = variation.max(0.0).min(1.0);
-        self.regenerate_tree();
+    pub fn set_twig_angle_variation(&mut self, variation: f32) -> Result<(), JsValue> {
+        self.update_params(|params| params.twigs.angle_variation = variation)
     }
 
     /// Export the tree as a GLTF file (returns JSON as string)
@@ -463,7 +1064,7 @@ impl TreeObject {
         root.push(gltf_json::buffer::View {
             buffer: gltf_json::Index::new(0),
             byte_length: gltf_json::validation::USize64::from(mesh.normals.len() * 4),
-            byte_offset: Some(gltf_json::validation::USize64::from(normals_offset as usize)),
+            byte_offset: Some(gltf_json::validation::USize64::from(normals_offset)),
             byte_stride: None,
             extensions: Default::default(),
             extras: Default::default(),
@@ -474,7 +1075,7 @@ impl TreeObject {
         root.push(gltf_json::buffer::View {
             buffer: gltf_json::Index::new(0),
             byte_length: gltf_json::validation::USize64::from(mesh.uvs.len() * 4),
-            byte_offset: Some(gltf_json::validation::USize64::from(uvs_offset as usize)),
+            byte_offset: Some(gltf_json::validation::USize64::from(uvs_offset)),
             byte_stride: None,
             extensions: Default::default(),
             extras: Default::default(),
@@ -485,7 +1086,7 @@ impl TreeObject {
         root.push(gltf_json::buffer::View {
             buffer: gltf_json::Index::new(0),
             byte_length: gltf_json::validation::USize64::from(mesh.indices.len() * 4),
-            byte_offset: Some(gltf_json::validation::USize64::from(indices_offset as usize)),
+            byte_offset: Some(gltf_json::validation::USize64::from(indices_offset)),
             byte_stride: None,
             extensions: Default::default(),
             extras: Default::default(),
@@ -561,9 +1162,102 @@ impl TreeObject {
     }
 }
 
+// Rust-only API for embedding the engine with custom subsystems
+impl TreeObject {
+    /// Create a tree using a custom generator, e.g. one built with
+    /// `ModularTreeGenerator::builder()` to swap in a different branching or
+    /// twig algorithm. Not available from JavaScript.
+    pub fn with_generator(params: TreeParameters, generator: ModularTreeGenerator) -> Result<TreeObject, ParameterError> {
+        let params = params.validated()?;
+        Ok(TreeObject::from_params_with_generator(params, generator))
+    }
+
+    pub fn params(&self) -> &TreeParameters {
+        &self.params
+    }
+
+    pub fn structure(&self) -> &structure::TreeStructure {
+        &self.tree
+    }
+}
+
+/// Convert a `ParameterError` into a plain JS object:
+/// `{ kind: "parse" | "validation", message, violations? }`.
+fn parameter_error(error: ParameterError) -> JsValue {
+    serde_wasm_bindgen::to_value(&error).unwrap_or_else(|_| JsValue::from_str(&error.to_string()))
+}
+
+/// Guide curve from a flat list of x, y, z triples and an interpolation name.
+fn parse_guide(points: Vec<f32>, interpolation: &str) -> Result<GuideCurve, JsValue> {
+    let interpolation: GuideInterpolation = serde_json::from_value(serde_json::Value::from(interpolation))
+        .map_err(|e| parameter_error(ParameterError::parse(e)))?;
+    if !points.len().is_multiple_of(3) {
+        let message = format!("Guide curve needs x, y, z triples but got {} numbers", points.len());
+        return Err(parameter_error(ParameterError::Parse { message }));
+    }
+    let points = points.chunks_exact(3).map(glam::Vec3::from_slice).collect();
+    Ok(GuideCurve::new(points, interpolation))
+}
+
 // Public API: generate a Tree
 #[wasm_bindgen]
 pub fn generate(seed: u32, trunk_height: f32, butressing: f32) -> Result<TreeObject, JsValue> {
     TreeObject::new(seed, trunk_height, butressing)
 }
 
+// Public API: list the built-in species presets accepted by `TreeObject::from_preset`
+#[wasm_bindgen]
+pub fn preset_names() -> Vec<String> {
+    core::PRESET_NAMES.iter().map(|name| name.to_string()).collect()
+}
+
+// Public API: machine-readable schema (name, group, type, range, step, default,
+// description) for every tree parameter, as JSON
+#[wasm_bindgen]
+pub fn parameter_schema() -> Result<String, JsValue> {
+    serde_json::to_string(&TreeParameters::schema())
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize parameter schema: {}", e)))
+}
+
+// Public API: validate a (possibly partial) parameter file without building a
+// tree. Returns the list of violations; an empty list means the file is valid.
+#[wasm_bindgen]
+pub fn validate_params(json: &str) -> Result<JsValue, JsValue> {
+    let params = TreeParameters::from_json(json).map_err(|e| parameter_error(ParameterError::parse(e)))?;
+    serde_wasm_bindgen::to_value(&params.validate())
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize violations: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ModifierEntry, ModifierParams, RandomPruneParams};
+
+    #[test]
+    fn edits_hit_the_picked_limb_after_random_pruning() {
+        let mut params = TreeParameters::default();
+        params.modifiers.push(ModifierEntry {
+            enabled: true,
+            modifier: ModifierParams::RandomPrune(RandomPruneParams { probability: 0.5, min_depth: 1 }),
+        });
+        let mut tree = TreeObject::from_params(params);
+
+        // A limb that the pruning has renumbered
+        let limb = tree.tree.branches.iter().rev().find(|branch| branch.order >= 2).unwrap();
+        let index = tree.tree.branch_members(limb.id)[0];
+        assert_ne!(tree.tree.skeleton_indices[index], index);
+        // The limb may have lost side branches to the pruning, so it is taken
+        // from the skeleton before the modifiers ran
+        let skeleton = tree.cache.stage_input(GenerationStage::Twigs).unwrap();
+        let skeleton_size = skeleton.cross_sections.len();
+        let limb = skeleton.subtree_indices(tree.tree.skeleton_indices[index]);
+        let centers: Vec<glam::Vec3> = limb.iter().map(|&i| skeleton.cross_sections[i].center).collect();
+        assert_eq!(centers[0], tree.tree.cross_sections[index].center);
+
+        tree.prune_branch(index).unwrap();
+        let skeleton = tree.cache.stage_input(GenerationStage::Twigs).unwrap();
+        assert!(tree.tree.skipped_edits.is_empty());
+        assert_eq!(skeleton.cross_sections.len(), skeleton_size - centers.len());
+        assert!(skeleton.cross_sections.iter().all(|cross_section| !centers.contains(&cross_section.center)));
+    }
+}
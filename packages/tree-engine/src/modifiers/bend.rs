@@ -0,0 +1,274 @@
+use crate::core::{GravitySagParams, WeightSagParams, WindBendParams};
+use crate::modifiers::TreeModifier;
+use crate::structure::{RingType, TreeStructure};
+use crate::trunk::RingGenerator;
+use glam::{Quat, Vec3};
+use rand::rngs::SmallRng;
+
+/// Bend the tree segment by segment. `local_bend` gets a cross-section index and
+/// the current (already bent) direction of the segment leading to it, and returns
+/// the extra rotation for that segment. Rotations accumulate down the hierarchy,
+/// so everything growing from a bent segment follows it.
+pub fn bend_segments(tree: &mut TreeStructure, mut local_bend: impl FnMut(usize, Vec3) -> Quat) {
+    let parents = tree.parent_indices();
+    let original_centers: Vec<Vec3> = tree.cross_sections.iter().map(|cs| cs.center).collect();
+    let mut centers = original_centers.clone();
+    let mut rotations = vec![Quat::IDENTITY; tree.cross_sections.len()];
+
+    for index in tree.parents_first_order() {
+        let Some(parent_idx) = parents[index] else { continue };
+
+        let segment = original_centers[index] - original_centers[parent_idx];
+        let direction = (rotations[parent_idx] * segment).normalize_or_zero();
+        let bend = if direction == Vec3::ZERO {
+            Quat::IDENTITY
+        } else {
+            local_bend(index, direction)
+        };
+
+        rotations[index] = (bend * rotations[parent_idx]).normalize();
+        centers[index] = centers[parent_idx] + rotations[index] * segment;
+    }
+
+    for (index, cross_section) in tree.cross_sections.iter_mut().enumerate() {
+        cross_section.center = centers[index];
+        cross_section.orientation = (rotations[index] * cross_section.orientation).normalize();
+    }
+
+    for twig in &mut tree.twigs {
+        let index = twig.cross_section_index;
+        if index < centers.len() {
+            twig.position = centers[index] + rotations[index] * (twig.position - original_centers[index]);
+            twig.orientation = (rotations[index] * twig.orientation).normalize();
+        }
+    }
+}
+
+/// Rotation turning `direction` toward `target` by `angle` radians, never past it.
+pub fn rotate_toward(direction: Vec3, target: Vec3, angle: f32) -> Quat {
+    let axis = direction.cross(target);
+    if axis.length_squared() < 1e-8 || angle <= 0.0 {
+        return Quat::IDENTITY;
+    }
+    let max_angle = direction.angle_between(target);
+    Quat::from_axis_angle(axis.normalize(), angle.min(max_angle))
+}
+
+impl TreeModifier for GravitySagParams {
+    fn apply(&self, tree: &mut TreeStructure, _rng: &mut SmallRng) {
+        let max_depth = tree.cross_sections.iter().map(|cs| cs.depth).max().unwrap_or(0).max(1);
+        let depths: Vec<u32> = tree.cross_sections.iter().map(|cs| cs.depth).collect();
+
+        bend_segments(tree, |index, direction| {
+            // Deeper (thinner) and more horizontal segments sag more; the trunk stays upright
+            let depth_factor = depths[index] as f32 / max_depth as f32;
+            let horizontal = (1.0 - direction.y * direction.y).max(0.0).sqrt();
+            let angle = self.strength * 0.15 * depth_factor * horizontal;
+            rotate_toward(direction, Vec3::NEG_Y, angle)
+        });
+    }
+}
+
+impl TreeModifier for WindBendParams {
+    fn apply(&self, tree: &mut TreeStructure, _rng: &mut SmallRng) {
+        let wind = Vec3::new(self.direction.x, 0.0, self.direction.z).normalize_or_zero();
+        if wind == Vec3::ZERO {
+            return;
+        }
+
+        let max_height = tree.cross_sections.iter().map(|cs| cs.center.y).fold(0.0f32, f32::max).max(0.01);
+        let heights: Vec<f32> = tree.cross_sections.iter().map(|cs| cs.center.y).collect();
+        let radii: Vec<f32> = tree.cross_sections.iter()
+            .map(|cs| cs.component_rings.first().map_or(0.0, |ring| ring.radius))
+            .collect();
+
+        bend_segments(tree, |index, direction| {
+            // High, thin branches are the most flexible
+            let height_factor = (heights[index] / max_height).clamp(0.0, 1.0);
+            let flexibility = 1.0 / (1.0 + radii[index] * 20.0);
+            let angle = self.strength * 0.1 * height_factor * flexibility;
+            rotate_toward(direction, wind, angle)
+        });
+    }
+}
+
+/// Downward turn in radians, at full strength, of a segment infinitely more
+/// loaded than its stiffness allows; a typically loaded segment gets half
+const MAX_WEIGHT_SAG_PER_SEGMENT: f32 = 0.12;
+
+impl TreeModifier for WeightSagParams {
+    fn apply(&self, tree: &mut TreeStructure, _rng: &mut SmallRng) {
+        let parents = tree.parent_indices();
+        let order = tree.parents_first_order();
+        let count = tree.cross_sections.len();
+
+        // Wood volume of the segment leading to each cross-section (pi dropped)
+        let ring_generator = RingGenerator::new();
+        let radii: Vec<f32> = tree.cross_sections.iter()
+            .map(|cs| ring_generator.effective_radius(&cs.component_rings))
+            .collect();
+        let lengths: Vec<f32> = (0..count)
+            .map(|index| parents[index].map_or(0.0, |parent| {
+                tree.cross_sections[index].center.distance(tree.cross_sections[parent].center)
+            }))
+            .collect();
+
+        // Supported mass and its centre of mass, accumulated from the tips down
+        let mut masses: Vec<f32> = (0..count).map(|index| radii[index] * radii[index] * lengths[index]).collect();
+        let mut weighted: Vec<Vec3> = (0..count).map(|index| tree.cross_sections[index].center * masses[index]).collect();
+        for &index in order.iter().rev() {
+            if let Some(parent) = parents[index] {
+                masses[parent] += masses[index];
+                let carried = weighted[index];
+                weighted[parent] += carried;
+            }
+        }
+
+        // Bending moment at the base of each segment is the supported mass times
+        // its horizontal lever arm; like a beam, the bend it causes falls off
+        // with radius^4. Roots are left alone.
+        let loads: Vec<Option<f32>> = (0..count)
+            .map(|index| {
+                let parent = parents[index]?;
+                let is_root = tree.cross_sections[index].component_rings.iter()
+                    .any(|ring| matches!(ring.ring_type, RingType::Root { .. }));
+                if is_root || masses[index] <= 0.0 {
+                    return None;
+                }
+                let lever = weighted[index] / masses[index] - tree.cross_sections[parent].center;
+                let moment = masses[index] * Vec3::new(lever.x, 0.0, lever.z).length();
+                Some(moment * lengths[index] / radii[index].max(1e-4).powi(4))
+            })
+            .collect();
+
+        // Loads span many orders of magnitude, so measure them against the
+        // tree's median and saturate: the same strength works for any size
+        let mut sorted: Vec<f32> = loads.iter().flatten().copied().filter(|load| *load > 0.0).collect();
+        if sorted.is_empty() {
+            return;
+        }
+        sorted.sort_by(f32::total_cmp);
+        let typical = sorted[sorted.len() / 2];
+
+        bend_segments(tree, |index, direction| {
+            let Some(load) = loads[index] else { return Quat::IDENTITY };
+            let relative = load / typical;
+            let angle = self.strength * MAX_WEIGHT_SAG_PER_SEGMENT * relative / (1.0 + relative);
+            rotate_toward(direction, Vec3::NEG_Y, angle)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::{BranchCrossSection, ComponentRing, RootType};
+    use glam::Vec2;
+    use rand::SeedableRng;
+
+    fn cross_section(center: Vec3, radius: f32, depth: u32, children_indices: Vec<usize>) -> BranchCrossSection {
+        BranchCrossSection {
+            center,
+            orientation: Quat::IDENTITY,
+            depth,
+            component_rings: vec![ComponentRing { offset: Vec2::ZERO, radius, ring_type: RingType::MainTrunk }],
+            children_indices,
+            parent_index: None,
+            branch_id: 0,
+        }
+    }
+
+    /// An upright three-section trunk (0, 1, 2) with two horizontal limbs
+    /// leaving its middle section, one along +x (3, 4) and one along -z (5, 6).
+    fn tree_with_level_limbs() -> TreeStructure {
+        let mut tree = TreeStructure::new();
+        tree.cross_sections = vec![
+            cross_section(Vec3::ZERO, 0.5, 0, vec![1]),
+            cross_section(Vec3::Y, 0.4, 0, vec![2, 3, 5]),
+            cross_section(Vec3::Y * 2.0, 0.3, 0, vec![]),
+            cross_section(Vec3::new(1.0, 1.0, 0.0), 0.1, 1, vec![4]),
+            cross_section(Vec3::new(2.0, 1.0, 0.0), 0.05, 1, vec![]),
+            cross_section(Vec3::new(0.0, 1.0, -1.0), 0.1, 1, vec![6]),
+            cross_section(Vec3::new(0.0, 1.0, -2.0), 0.05, 1, vec![]),
+        ];
+        tree.build_hierarchy();
+        tree
+    }
+
+    #[test]
+    fn gravity_sag_lowers_the_tips_and_keeps_the_trunk_base() {
+        let original = tree_with_level_limbs();
+        let mut tree = original.clone();
+        GravitySagParams { strength: 1.0 }.apply(&mut tree, &mut SmallRng::seed_from_u64(1));
+
+        // The upright trunk doesn't move at all
+        for index in 0..3 {
+            assert_eq!(tree.cross_sections[index].center, original.cross_sections[index].center);
+        }
+        for tip in [4, 6] {
+            assert!(tree.cross_sections[tip].center.y < original.cross_sections[tip].center.y - 0.1, "tip {}", tip);
+        }
+    }
+
+    /// Downward angle, in radians, of the segment from `from` to `to`.
+    fn droop(tree: &TreeStructure, from: usize, to: usize) -> f32 {
+        let direction = (tree.cross_sections[to].center - tree.cross_sections[from].center).normalize();
+        -direction.y.asin()
+    }
+
+    #[test]
+    fn weight_sag_bends_long_thin_limbs_most() {
+        let mut tree = TreeStructure::new();
+        // A long thin limb along +x (2-5) and a short thick one along -x (6),
+        // both level and leaving the middle of the trunk
+        tree.cross_sections = vec![
+            cross_section(Vec3::ZERO, 0.5, 0, vec![1]),
+            cross_section(Vec3::Y, 0.4, 0, vec![7, 2, 6]),
+            cross_section(Vec3::new(1.0, 1.0, 0.0), 0.05, 1, vec![3]),
+            cross_section(Vec3::new(2.0, 1.0, 0.0), 0.04, 1, vec![4]),
+            cross_section(Vec3::new(3.0, 1.0, 0.0), 0.03, 1, vec![5]),
+            cross_section(Vec3::new(4.0, 1.0, 0.0), 0.02, 1, vec![]),
+            cross_section(Vec3::new(-1.0, 1.0, 0.0), 0.2, 1, vec![]),
+            cross_section(Vec3::Y * 2.0, 0.3, 0, vec![]),
+        ];
+        tree.build_hierarchy();
+        WeightSagParams { strength: 1.0 }.apply(&mut tree, &mut SmallRng::seed_from_u64(1));
+
+        let thin = droop(&tree, 1, 2);
+        let thick = droop(&tree, 1, 6);
+        assert!(thick >= 0.0);
+        assert!(thin > thick * 2.0, "thin {} thick {}", thin, thick);
+    }
+
+    #[test]
+    fn weight_sag_leaves_roots_alone() {
+        let mut tree = tree_with_level_limbs();
+        let mut root = cross_section(Vec3::new(1.0, -0.2, 0.0), 0.1, 0, vec![8]);
+        root.component_rings[0].ring_type = RingType::Root { root_type: RootType::TapRoot };
+        let mut root_tip = cross_section(Vec3::new(2.0, -0.4, 0.0), 0.05, 0, vec![]);
+        root_tip.component_rings[0].ring_type = RingType::Root { root_type: RootType::TapRoot };
+        tree.cross_sections[0].children_indices.push(7);
+        tree.cross_sections.extend([root, root_tip]);
+        tree.build_hierarchy();
+        let original = tree.clone();
+
+        WeightSagParams { strength: 1.0 }.apply(&mut tree, &mut SmallRng::seed_from_u64(1));
+
+        assert!(tree.cross_sections[4].center.y < original.cross_sections[4].center.y);
+        for index in [7, 8] {
+            assert_eq!(tree.cross_sections[index].center, original.cross_sections[index].center);
+        }
+    }
+
+    #[test]
+    fn weight_sag_leaves_a_single_cross_section_alone() {
+        let mut tree = TreeStructure::new();
+        tree.cross_sections = vec![cross_section(Vec3::Y, 0.5, 0, vec![])];
+        tree.build_hierarchy();
+
+        WeightSagParams { strength: 1.0 }.apply(&mut tree, &mut SmallRng::seed_from_u64(1));
+
+        assert_eq!(tree.cross_sections[0].center, Vec3::Y);
+        assert_eq!(tree.cross_sections[0].orientation, Quat::IDENTITY);
+    }
+}
@@ -0,0 +1,93 @@
+pub mod bend;
+pub mod noise;
+pub mod prune;
+pub mod smoothing;
+
+use crate::core::{ModifierEntry, ModifierParams, sub_seed};
+use crate::structure::TreeStructure;
+use rand::{RngCore, SeedableRng, rngs::SmallRng};
+
+pub use bend::bend_segments;
+
+/// A post-processing step run over the finished tree (cross-sections and twigs)
+/// after the generation pipeline.
+pub trait TreeModifier {
+    fn apply(&self, tree: &mut TreeStructure, rng: &mut SmallRng);
+}
+
+impl TreeModifier for ModifierParams {
+    fn apply(&self, tree: &mut TreeStructure, rng: &mut SmallRng) {
+        match self {
+            ModifierParams::GravitySag(params) => params.apply(tree, rng),
+            ModifierParams::WindBend(params) => params.apply(tree, rng),
+            ModifierParams::RandomPrune(params) => params.apply(tree, rng),
+            ModifierParams::RadiusNoise(params) => params.apply(tree, rng),
+            ModifierParams::Smoothing(params) => params.apply(tree, rng),
+            ModifierParams::WeightSag(params) => params.apply(tree, rng),
+        }
+    }
+}
+
+/// Run the enabled entries of a modifier stack in order. Each entry draws from
+/// its own RNG, so toggling one modifier doesn't change the others.
+pub fn apply_modifiers(entries: &[ModifierEntry], tree: &mut TreeStructure, rng: &mut SmallRng) {
+    let stream_seed = rng.next_u64();
+
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.enabled {
+            let mut modifier_rng = SmallRng::seed_from_u64(sub_seed(stream_seed, index as u64));
+            entry.modifier.apply(tree, &mut modifier_rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{GravitySagParams, RadiusNoiseParams, RandomPruneParams, SmoothingParams, TreeParameters, generator::ModularTreeGenerator};
+
+    fn entry(enabled: bool, modifier: ModifierParams) -> ModifierEntry {
+        ModifierEntry { enabled, modifier }
+    }
+
+    fn radii(tree: &TreeStructure) -> Vec<f32> {
+        tree.cross_sections.iter().map(|cs| cs.component_rings[0].radius).collect()
+    }
+
+    #[test]
+    fn disabled_entries_leave_the_tree_unchanged() {
+        let original = ModularTreeGenerator::new().generate_tree(&TreeParameters::default());
+        let entries = vec![
+            entry(false, ModifierParams::GravitySag(GravitySagParams { strength: 1.0 })),
+            entry(false, ModifierParams::RandomPrune(RandomPruneParams { probability: 1.0, min_depth: 0 })),
+            entry(false, ModifierParams::RadiusNoise(RadiusNoiseParams { amount: 1.0 })),
+            entry(false, ModifierParams::Smoothing(SmoothingParams { iterations: 5, strength: 1.0 })),
+        ];
+
+        let mut tree = original.clone();
+        apply_modifiers(&entries, &mut tree, &mut SmallRng::seed_from_u64(7));
+
+        assert_eq!(tree.cross_sections.len(), original.cross_sections.len());
+        assert_eq!(tree.twigs.len(), original.twigs.len());
+        for (a, b) in tree.cross_sections.iter().zip(&original.cross_sections) {
+            assert_eq!(a.center, b.center);
+        }
+        assert_eq!(radii(&tree), radii(&original));
+    }
+
+    #[test]
+    fn toggling_an_entry_keeps_the_others_draws() {
+        let original = ModularTreeGenerator::new().generate_tree(&TreeParameters::default());
+        // A prune that draws for every side limb but can never remove one
+        let prune = ModifierParams::RandomPrune(RandomPruneParams { probability: 0.5, min_depth: u32::MAX });
+        let noise = ModifierParams::RadiusNoise(RadiusNoiseParams { amount: 0.5 });
+
+        let mut with_prune = original.clone();
+        apply_modifiers(&[entry(true, prune.clone()), entry(true, noise.clone())], &mut with_prune, &mut SmallRng::seed_from_u64(7));
+        let mut without_prune = original.clone();
+        apply_modifiers(&[entry(false, prune), entry(true, noise)], &mut without_prune, &mut SmallRng::seed_from_u64(7));
+
+        assert_ne!(radii(&with_prune), radii(&original));
+        assert_eq!(radii(&with_prune), radii(&without_prune));
+    }
+}
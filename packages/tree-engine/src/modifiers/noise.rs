@@ -0,0 +1,23 @@
+use crate::core::RadiusNoiseParams;
+use crate::modifiers::TreeModifier;
+use crate::structure::TreeStructure;
+use rand::{Rng, rngs::SmallRng};
+
+impl TreeModifier for RadiusNoiseParams {
+    fn apply(&self, tree: &mut TreeStructure, rng: &mut SmallRng) {
+        let parents = tree.parent_indices();
+        let mut noise = vec![0.0f32; tree.cross_sections.len()];
+
+        // Blend each cross-section's noise with its parent's so radii vary
+        // smoothly along a limb instead of jumping between segments
+        for index in tree.parents_first_order() {
+            let parent_noise = parents[index].map_or(0.0, |parent_idx| noise[parent_idx]);
+            noise[index] = parent_noise * 0.6 + rng.gen_range(-1.0..=1.0) * 0.4;
+
+            let factor = (1.0 + self.amount * noise[index]).max(0.1);
+            for ring in &mut tree.cross_sections[index].component_rings {
+                ring.radius *= factor;
+            }
+        }
+    }
+}
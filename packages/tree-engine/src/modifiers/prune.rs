@@ -0,0 +1,63 @@
+use crate::core::RandomPruneParams;
+use crate::modifiers::TreeModifier;
+use crate::structure::TreeStructure;
+use rand::{Rng, rngs::SmallRng};
+
+impl TreeModifier for RandomPruneParams {
+    fn apply(&self, tree: &mut TreeStructure, rng: &mut SmallRng) {
+        let radius = |index: usize| {
+            tree.cross_sections[index].component_rings.iter().map(|ring| ring.radius).fold(0.0f32, f32::max)
+        };
+
+        // Side limbs start wherever a cross-section has more than one child. The
+        // thickest child continues the parent limb and is never pruned.
+        let mut pruned = Vec::new();
+        for cross_section in &tree.cross_sections {
+            let children = &cross_section.children_indices;
+            if children.len() < 2 {
+                continue;
+            }
+            let leader = children.iter().copied().max_by(|&a, &b| radius(a).total_cmp(&radius(b)));
+            for &child_idx in children {
+                let child_is_eligible = Some(child_idx) != leader
+                    && tree.cross_sections[child_idx].depth >= self.min_depth;
+                if rng.gen_range(0.0..1.0) < self.probability && child_is_eligible {
+                    pruned.push(child_idx);
+                }
+            }
+        }
+
+        tree.remove_subtrees(&pruned);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{TreeParameters, generator::ModularTreeGenerator};
+    use glam::Vec3;
+    use rand::SeedableRng;
+
+    #[test]
+    fn random_prune_keeps_every_leader() {
+        let original = ModularTreeGenerator::new().generate_tree(&TreeParameters::default());
+        let mut tree = original.clone();
+        RandomPruneParams { probability: 1.0, min_depth: 0 }.apply(&mut tree, &mut SmallRng::seed_from_u64(1));
+
+        // Pruning every side limb leaves exactly the chain of leaders from the root
+        let radius = |index: usize| original.cross_sections[index].component_rings.iter().map(|ring| ring.radius).fold(0.0f32, f32::max);
+        let mut leaders = Vec::new();
+        let mut next = Some(0);
+        while let Some(index) = next {
+            leaders.push(index);
+            next = original.cross_sections[index].children_indices.iter().copied()
+                .max_by(|&a, &b| radius(a).total_cmp(&radius(b)));
+        }
+        leaders.sort_unstable();
+        assert!(leaders.len() > 1);
+
+        let remaining: Vec<Vec3> = tree.cross_sections.iter().map(|cs| cs.center).collect();
+        let expected: Vec<Vec3> = leaders.iter().map(|&index| original.cross_sections[index].center).collect();
+        assert_eq!(remaining, expected);
+    }
+}
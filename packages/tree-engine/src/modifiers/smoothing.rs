@@ -0,0 +1,66 @@
+use crate::core::SmoothingParams;
+use crate::modifiers::TreeModifier;
+use crate::structure::TreeStructure;
+use glam::{Quat, Vec3};
+use rand::rngs::SmallRng;
+
+impl TreeModifier for SmoothingParams {
+    fn apply(&self, tree: &mut TreeStructure, _rng: &mut SmallRng) {
+        let parents = tree.parent_indices();
+
+        for _ in 0..self.iterations {
+            let centers: Vec<Vec3> = tree.cross_sections.iter().map(|cs| cs.center).collect();
+            let mut offsets = vec![Vec3::ZERO; centers.len()];
+
+            // Only plain segments inside a limb move; forks, tips and the root stay put
+            for (index, cross_section) in tree.cross_sections.iter_mut().enumerate() {
+                let (Some(parent_idx), [child_idx]) = (parents[index], cross_section.children_indices.as_slice()) else {
+                    continue;
+                };
+
+                let midpoint = (centers[parent_idx] + centers[*child_idx]) * 0.5;
+                let new_center = centers[index].lerp(midpoint, self.strength);
+                let old_direction = (centers[*child_idx] - centers[parent_idx]).normalize_or_zero();
+                let new_direction = (centers[*child_idx] - new_center).normalize_or_zero();
+                if old_direction != Vec3::ZERO && new_direction != Vec3::ZERO {
+                    let turn = Quat::from_rotation_arc(old_direction, new_direction);
+                    cross_section.orientation = (turn * cross_section.orientation).normalize();
+                }
+
+                offsets[index] = new_center - centers[index];
+                cross_section.center = new_center;
+            }
+
+            for twig in &mut tree.twigs {
+                if let Some(offset) = offsets.get(twig.cross_section_index) {
+                    twig.position += *offset;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{TreeParameters, generator::ModularTreeGenerator};
+    use rand::SeedableRng;
+
+    #[test]
+    fn smoothing_keeps_forks_and_tips_in_place() {
+        let original = ModularTreeGenerator::new().generate_tree(&TreeParameters::default());
+        let mut tree = original.clone();
+        SmoothingParams { iterations: 5, strength: 1.0 }.apply(&mut tree, &mut SmallRng::seed_from_u64(1));
+
+        let mut moved = 0;
+        for (index, (smoothed, cross_section)) in tree.cross_sections.iter().zip(&original.cross_sections).enumerate() {
+            let is_plain_segment = cross_section.parent_index.is_some() && cross_section.children_indices.len() == 1;
+            if is_plain_segment {
+                moved += usize::from(smoothed.center != cross_section.center);
+            } else {
+                assert_eq!(smoothed.center, cross_section.center, "cross-section {}", index);
+            }
+        }
+        assert!(moved > 0);
+    }
+}
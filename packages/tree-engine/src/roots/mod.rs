@@ -1,5 +1,5 @@
-use crate::core::{RootGenerator, RootParams, TreeSubsystem};
-use crate::structure::{TreeStructure, BranchCrossSection, ComponentRing, RingType};
+use crate::core::{GenerationContext, RootGenerator, RootParams, TreeSubsystem};
+use crate::structure::{TreeStructure, BranchCrossSection, ComponentRing, RingType, RootType};
 use glam::{Vec3, Quat};
 use rand::rngs::SmallRng;
 
@@ -16,7 +16,9 @@ impl TreeSubsystem for RootSystem {
     type Output = ();
     
     fn generate(
+        &self,
         params: &Self::Params,
+        _context: &GenerationContext,
         tree: &mut TreeStructure,
         _rng: &mut SmallRng,
     ) -> Self::Output {
@@ -38,10 +40,13 @@ impl TreeSubsystem for RootSystem {
                 ComponentRing {
                     offset: trunk_ring.offset,
                     radius: trunk_ring.radius * taper_factor,
-                    ring_type: RingType::MainTrunk, // TODO: Use Root type when implemented
+                    ring_type: RingType::Root { root_type: RootType::TapRoot },
                 }
             }).collect();
             
+            let root_index = tree.cross_sections.len();
+            let parent_index = if segment == 0 { 0 } else { root_index - 1 };
+
             // Create root cross-section that extends trunk downward
             let root_cross_section = BranchCrossSection {
                 center: root_center,
@@ -49,14 +54,11 @@ impl TreeSubsystem for RootSystem {
                 depth: 0,
                 component_rings: root_rings,
                 children_indices: Vec::new(),
+                parent_index: Some(parent_index),
+                branch_id: 0,
             };
             
-            let root_index = tree.cross_sections.len();
-            if segment == 0 {
-                tree.cross_sections[0].children_indices.push(root_index);
-            } else {
-                tree.cross_sections[root_index - 1].children_indices.push(root_index);
-            }
+            tree.cross_sections[parent_index].children_indices.push(root_index);
             tree.cross_sections.push(root_cross_section);
         }
     }
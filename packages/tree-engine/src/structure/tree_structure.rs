@@ -1,9 +1,19 @@
 use glam::{Vec2, Vec3, Quat};
+use std::ops::Range;
 
-#[derive(Debug)]
+/// Slack allowed past the end of a branch when looking up a distance along
+/// it, so rounding never loses an anchor on the tip
+const BRANCH_DISTANCE_TOLERANCE: f32 = 1e-3;
+
+#[derive(Debug, Clone)]
 pub struct TreeStructure {
     pub cross_sections: Vec<BranchCrossSection>,
     pub twigs: Vec<Twig>,       // Collection of all twigs in the tree
+    pub budget_exhausted: bool, // Growth stopped early at general.max_cross_sections
+    pub skipped_edits: Vec<usize>, // Skeleton edits whose anchor did not exist
+    pub branches: Vec<Branch>,  // Limbs, parents before children; rebuilt by build_hierarchy
+    pub branch_cross_sections: Vec<usize>, // Cross-sections of every branch, indexed by Branch::cross_sections
+    pub skeleton_indices: Vec<usize>, // Index of each cross-section in the skeleton left by the edits, kept through later removals
 }
 
 #[derive(Debug, Clone)]
@@ -18,6 +28,21 @@ pub struct BranchCrossSection {
 
     // Cross-section level connectivity
     pub children_indices: Vec<usize>, // Indices of child cross-sections
+    pub parent_index: Option<usize>,  // Cross-section this one grows from (None for the root)
+    pub branch_id: usize,             // Branch this cross-section belongs to, set by build_hierarchy
+}
+
+/// One limb: a chain of cross-sections from where it leaves its parent to its
+/// tip. At every fork the thickest child growing forward continues the limb
+/// and the other children start new branches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Branch {
+    pub id: usize,                    // Index in TreeStructure::branches
+    pub parent_branch: Option<usize>, // Branch this one grows from (None for the trunk)
+    pub order: u32,                   // 0 for the trunk, parent order + 1 otherwise
+    pub cross_sections: Range<usize>, // Range of TreeStructure::branch_cross_sections, base first
+    pub length: f32,                  // Measured along the centers from the attachment point
+    pub base_radius: f32,             // Effective radius of the first cross-section
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +60,7 @@ pub enum RingType {
     Root { root_type: RootType },
 }
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum RootType {
     TapRoot,        // Deep central root
@@ -49,6 +75,7 @@ pub struct RingMesh {
     pub uvs: Vec<Vec2>,
     pub indices: Vec<u32>,
     pub depths: Vec<u32>, // Depth value for each vertex
+    pub branch_ids: Vec<u32>, // Branch of each vertex, for selecting whole limbs
     pub height_factors: Vec<f32>, // Height-based blend factor for bark texture (0=trunk, 1=high branches)
 }
 
@@ -63,6 +90,7 @@ pub struct Twig {
     pub orientation: Quat,      // Twig orientation in world space
     pub scale: f32,             // Size scaling factor (0.5-3.0)
     pub twig_type: TwigType,    // Type of twig for rendering
+    pub cross_section_index: usize, // Cross-section the twig is attached to
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -86,6 +114,236 @@ impl TreeStructure {
         TreeStructure{
             cross_sections: Vec::new(),
             twigs: Vec::new(),
+            budget_exhausted: false,
+            skipped_edits: Vec::new(),
+            branches: Vec::new(),
+            branch_cross_sections: Vec::new(),
+            skeleton_indices: Vec::new(),
+        }
+    }
+
+    /// Cross-section indices of a branch, from its base to its tip.
+    pub fn branch_members(&self, branch_id: usize) -> &[usize] {
+        self.branches
+            .get(branch_id)
+            .map_or(&[], |branch| &self.branch_cross_sections[branch.cross_sections.clone()])
+    }
+
+    /// Distance from where a branch leaves its parent to each of its
+    /// cross-sections, base first, measured like `Branch::length`.
+    fn member_distances(&self, branch_id: usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+        let members = self.branch_members(branch_id);
+        let mut previous = members.first().and_then(|&base| self.cross_sections[base].parent_index);
+        let mut distance = 0.0;
+        members.iter().map(move |&index| {
+            if let Some(previous) = previous {
+                distance += self.cross_sections[previous].center.distance(self.cross_sections[index].center);
+            }
+            previous = Some(index);
+            (index, distance)
+        })
+    }
+
+    /// How far along its branch a cross-section lies.
+    pub fn distance_along_branch(&self, index: usize) -> Option<f32> {
+        let branch_id = self.cross_sections.get(index)?.branch_id;
+        self.member_distances(branch_id).find(|&(member, _)| member == index).map(|(_, distance)| distance)
+    }
+
+    /// The cross-section of a branch closest to `distance` along it, or `None`
+    /// when the branch does not exist or ends before `distance`.
+    pub fn cross_section_along_branch(&self, branch_id: usize, distance: f32) -> Option<usize> {
+        let branch = self.branches.get(branch_id)?;
+        if distance > branch.length + BRANCH_DISTANCE_TOLERANCE {
+            return None;
+        }
+        self.member_distances(branch_id)
+            .min_by(|(_, a), (_, b)| (a - distance).abs().total_cmp(&(b - distance).abs()))
+            .map(|(index, _)| index)
+    }
+
+    /// Refresh the parent links and the branch graph from `children_indices`.
+    /// Branch ids depend only on the shape of the tree, so the same tree always
+    /// gets the same ids. Roots are numbered after every limb above ground, so
+    /// adding or removing them leaves the crown's ids alone.
+    pub fn build_hierarchy(&mut self) {
+        let parents = self.parent_indices();
+        for (cross_section, parent) in self.cross_sections.iter_mut().zip(&parents) {
+            cross_section.parent_index = *parent;
+        }
+
+        self.branches.clear();
+        self.branch_cross_sections.clear();
+
+        // Branches still to walk: (first cross-section, parent branch, order)
+        let mut pending: Vec<(usize, Option<usize>, u32)> = parents
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, parent)| parent.is_none())
+            .map(|(root, _)| (root, None, 0))
+            .collect();
+
+        while let Some((start, parent_branch, order)) = pending.pop() {
+            let id = self.branches.len();
+            let first = self.branch_cross_sections.len();
+            let mut length = parents[start].map_or(0.0, |parent| {
+                self.cross_sections[parent].center.distance(self.cross_sections[start].center)
+            });
+
+            let mut side_branches = Vec::new();
+            let mut current = start;
+            loop {
+                self.branch_cross_sections.push(current);
+                self.cross_sections[current].branch_id = id;
+                let Some(leader) = self.leading_child(current) else { break };
+                side_branches.extend(
+                    self.cross_sections[current].children_indices.iter().copied().filter(|&child| child != leader),
+                );
+                length += self.cross_sections[current].center.distance(self.cross_sections[leader].center);
+                current = leader;
+            }
+
+            let base_radius = self.cross_sections[start]
+                .component_rings
+                .iter()
+                .map(|ring| ring.radius * ring.radius)
+                .sum::<f32>()
+                .sqrt();
+            self.branches.push(Branch {
+                id,
+                parent_branch,
+                order,
+                cross_sections: first..self.branch_cross_sections.len(),
+                length,
+                base_radius,
+            });
+
+            // Side branches are numbered in the order they leave this limb,
+            // roots last
+            side_branches.sort_by_key(|&child| self.is_root(child));
+            pending.extend(side_branches.into_iter().rev().map(|child| (child, Some(id), order + 1)));
+        }
+    }
+
+    fn is_root(&self, index: usize) -> bool {
+        self.cross_sections[index]
+            .component_rings
+            .iter()
+            .any(|ring| matches!(ring.ring_type, RingType::Root { .. }))
+    }
+
+    /// Child that continues the limb through `index`: the thickest child growing
+    /// forward along the cross-section's axis, or the thickest child if none does.
+    fn leading_child(&self, index: usize) -> Option<usize> {
+        let cross_section = &self.cross_sections[index];
+        let axis = cross_section.orientation * Vec3::Y;
+        let radius = |child: usize| {
+            self.cross_sections[child].component_rings.iter().map(|ring| ring.radius).fold(0.0f32, f32::max)
+        };
+        let forward = |child: &usize| (self.cross_sections[*child].center - cross_section.center).dot(axis) > 0.0;
+        let thickest = |children: &mut dyn Iterator<Item = usize>| children.max_by(|&a, &b| radius(a).total_cmp(&radius(b)));
+
+        let children = &cross_section.children_indices;
+        thickest(&mut children.iter().copied().filter(forward)).or_else(|| thickest(&mut children.iter().copied()))
+    }
+
+    /// Parent of every cross-section (`None` for the root).
+    pub fn parent_indices(&self) -> Vec<Option<usize>> {
+        let mut parents = vec![None; self.cross_sections.len()];
+        for (index, cross_section) in self.cross_sections.iter().enumerate() {
+            for &child_idx in &cross_section.children_indices {
+                parents[child_idx] = Some(index);
+            }
+        }
+        parents
+    }
+
+    /// Every cross-section ordered so that parents come before their children.
+    pub fn parents_first_order(&self) -> Vec<usize> {
+        self.parent_indices()
+            .iter()
+            .enumerate()
+            .filter(|(_, parent)| parent.is_none())
+            .flat_map(|(root, _)| self.subtree_indices(root))
+            .collect()
+    }
+
+    /// A cross-section and all of its descendants, parents before children.
+    pub fn subtree_indices(&self, root: usize) -> Vec<usize> {
+        let mut indices = Vec::new();
+        let mut stack = vec![root];
+        while let Some(index) = stack.pop() {
+            indices.push(index);
+            stack.extend(self.cross_sections[index].children_indices.iter().rev());
+        }
+        indices
+    }
+
+    /// Remove the given cross-sections, everything growing from them and the
+    /// twigs attached to them. The remaining cross-sections are re-indexed.
+    pub fn remove_subtrees(&mut self, roots: &[usize]) {
+        let count = self.cross_sections.len();
+        let mut removed = vec![false; count];
+        for &root in roots.iter().filter(|&&root| root < count) {
+            for index in self.subtree_indices(root) {
+                removed[index] = true;
+            }
+        }
+
+        // Map old indices to their position after compaction
+        let mut remap = vec![usize::MAX; count];
+        let mut next_index = 0;
+        for (index, is_removed) in removed.iter().enumerate() {
+            if !is_removed {
+                remap[index] = next_index;
+                next_index += 1;
+            }
+        }
+
+        let cross_sections = std::mem::take(&mut self.cross_sections);
+        self.cross_sections = cross_sections
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !removed[*index])
+            .map(|(_, mut cross_section)| {
+                cross_section.children_indices = cross_section.children_indices
+                    .iter()
+                    .filter(|&&child_idx| !removed[child_idx])
+                    .map(|&child_idx| remap[child_idx])
+                    .collect();
+                cross_section.parent_index = cross_section.parent_index.map(|parent| remap[parent]);
+                cross_section
+            })
+            .collect();
+
+        if self.skeleton_indices.len() == count {
+            let mut removed = removed.iter();
+            self.skeleton_indices.retain(|_| !removed.next().copied().unwrap_or(false));
+        }
+
+        self.twigs.retain(|twig| !removed.get(twig.cross_section_index).copied().unwrap_or(true));
+        for twig in &mut self.twigs {
+            twig.cross_section_index = remap[twig.cross_section_index];
+        }
+    }
+
+    /// Rotate a cross-section's subtree (and its twigs) by `rotation` around
+    /// `pivot`, then move it by `translation`.
+    pub fn transform_subtree(&mut self, root: usize, pivot: Vec3, rotation: Quat, translation: Vec3) {
+        let mut in_subtree = vec![false; self.cross_sections.len()];
+        for index in self.subtree_indices(root) {
+            in_subtree[index] = true;
+            let cross_section = &mut self.cross_sections[index];
+            cross_section.center = pivot + rotation * (cross_section.center - pivot) + translation;
+            cross_section.orientation = (rotation * cross_section.orientation).normalize();
+        }
+
+        for twig in &mut self.twigs {
+            if in_subtree.get(twig.cross_section_index).copied().unwrap_or(false) {
+                twig.position = pivot + rotation * (twig.position - pivot) + translation;
+                twig.orientation = (rotation * twig.orientation).normalize();
+            }
         }
     }
 
@@ -95,6 +353,7 @@ impl TreeStructure {
         let mut uvs = Vec::new();
         let mut indices = Vec::new();
         let mut depths = Vec::new();
+        let mut branch_ids = Vec::new();
         let mut height_factors = Vec::new();
 
         // Generate geometry for each cross-section (unified perimeter from multiple rings)
@@ -118,12 +377,16 @@ impl TreeStructure {
                     &mut depths,
                     &mut height_factors,
                 );
+                // The tube up to a cross-section belongs to that cross-section's branch
+                let added = vertices.len() - branch_ids.len();
+                branch_ids.extend(std::iter::repeat_n(self.cross_sections[child_idx].branch_id as u32, added));
             }
         }
 
-        RingMesh { vertices, normals, uvs, indices, depths, height_factors }
+        RingMesh { vertices, normals, uvs, indices, depths, branch_ids, height_factors }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn connect_cross_section_perimeters_with_depth(
         &self,
         parent_geo: &CrossSectionGeometry,
@@ -193,7 +456,7 @@ impl TreeStructure {
             // V coordinate: along branch length, with height-based scaling
             // Lower sections (trunk) get larger V scale for detailed bark
             // Higher sections (branches) get smaller V scale for smoother appearance
-            let v_scale_parent = 1.0 + parent_blend_factor * 2.0; // 1x to 3x scaling
+            let _v_scale_parent = 1.0 + parent_blend_factor * 2.0; // 1x to 3x scaling
             let v_scale_child = 1.0 + child_blend_factor * 2.0;
             
             let v_parent = 0.0;
@@ -225,6 +488,12 @@ impl TreeStructure {
     }
 }
 
+impl Default for TreeStructure {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl BranchCrossSection {
     pub fn generate_unified_geometry(&self, resolution: u32) -> CrossSectionGeometry {
         let points = Vec::new();
@@ -335,3 +604,142 @@ impl BranchCrossSection {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cross_section(center: Vec3, radius: f32, children_indices: Vec<usize>) -> BranchCrossSection {
+        BranchCrossSection {
+            center,
+            orientation: Quat::IDENTITY,
+            depth: 0,
+            component_rings: vec![ComponentRing { offset: Vec2::ZERO, radius, ring_type: RingType::MainTrunk }],
+            children_indices,
+            parent_index: None,
+            branch_id: 0,
+        }
+    }
+
+    fn twig(cross_section_index: usize) -> Twig {
+        Twig {
+            position: Vec3::ZERO,
+            orientation: Quat::IDENTITY,
+            scale: 1.0,
+            twig_type: TwigType::LeafCluster,
+            cross_section_index,
+        }
+    }
+
+    /// A three-section trunk (0, 2, 3) with a thin side branch (1) leaving
+    /// the middle section, stored out of order, and a twig on each tip.
+    fn forked_tree() -> TreeStructure {
+        let mut tree = TreeStructure::new();
+        tree.cross_sections = vec![
+            cross_section(Vec3::ZERO, 0.5, vec![2]),
+            cross_section(Vec3::new(1.0, 1.2, 0.0), 0.1, vec![]),
+            cross_section(Vec3::Y, 0.4, vec![1, 3]),
+            cross_section(Vec3::Y * 2.0, 0.3, vec![]),
+        ];
+        tree.twigs = vec![twig(1), twig(3)];
+        tree.build_hierarchy();
+        tree
+    }
+
+    #[test]
+    fn build_hierarchy_follows_the_thickest_child() {
+        let tree = forked_tree();
+
+        let parents: Vec<Option<usize>> = tree.cross_sections.iter().map(|cross_section| cross_section.parent_index).collect();
+        assert_eq!(parents, vec![None, Some(2), Some(0), Some(2)]);
+
+        assert_eq!(tree.branches.len(), 2);
+        assert_eq!(tree.branch_members(0), &[0, 2, 3]);
+        assert_eq!(tree.branch_members(1), &[1]);
+        assert_eq!(tree.branches[0].parent_branch, None);
+        assert_eq!(tree.branches[0].order, 0);
+        assert_eq!(tree.branches[0].length, 2.0);
+        assert_eq!(tree.branches[1].parent_branch, Some(0));
+        assert_eq!(tree.branches[1].order, 1);
+        assert_eq!(tree.branches[1].base_radius, 0.1);
+
+        let branch_ids: Vec<usize> = tree.cross_sections.iter().map(|cross_section| cross_section.branch_id).collect();
+        assert_eq!(branch_ids, vec![0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn side_branches_are_numbered_along_their_parent() {
+        let mut tree = TreeStructure::new();
+        tree.cross_sections = vec![
+            cross_section(Vec3::ZERO, 0.5, vec![3, 1]),
+            cross_section(Vec3::Y, 0.4, vec![4, 2]),
+            cross_section(Vec3::Y * 2.0, 0.3, vec![]),
+            cross_section(Vec3::new(1.0, 0.5, 0.0), 0.1, vec![]),
+            cross_section(Vec3::new(-1.0, 1.5, 0.0), 0.1, vec![5]),
+            cross_section(Vec3::new(-2.0, 2.0, 0.0), 0.05, vec![]),
+        ];
+        tree.build_hierarchy();
+        let first = tree.branches.clone();
+
+        // The lower side branch comes first, then the upper one and its tip
+        assert_eq!(tree.branch_members(1), &[3]);
+        assert_eq!(tree.branch_members(2), &[4, 5]);
+        assert!(tree.branches[1..].iter().all(|branch| branch.parent_branch == Some(0)));
+
+        tree.build_hierarchy();
+        assert_eq!(tree.branches, first);
+    }
+
+    #[test]
+    fn roots_are_numbered_after_the_crown() {
+        let mut tree = forked_tree();
+        let mut root = cross_section(Vec3::new(0.0, -1.0, 0.0), 0.2, vec![]);
+        root.component_rings[0].ring_type = RingType::Root { root_type: RootType::TapRoot };
+        tree.cross_sections[0].children_indices.push(4);
+        tree.cross_sections.push(root);
+        tree.build_hierarchy();
+
+        // The root leaves the trunk first but keeps the side branch's id free
+        assert_eq!(tree.branch_members(1), &[1]);
+        assert_eq!(tree.branch_members(2), &[4]);
+    }
+
+    #[test]
+    fn anchors_are_found_by_distance_along_the_branch() {
+        let tree = forked_tree();
+        assert_eq!(tree.distance_along_branch(0), Some(0.0));
+        assert_eq!(tree.distance_along_branch(3), Some(2.0));
+        assert_eq!(tree.distance_along_branch(1), Some(Vec3::new(1.0, 0.2, 0.0).length()));
+        assert_eq!(tree.distance_along_branch(4), None);
+
+        assert_eq!(tree.cross_section_along_branch(0, 1.2), Some(2));
+        assert_eq!(tree.cross_section_along_branch(0, 2.0), Some(3));
+        assert_eq!(tree.cross_section_along_branch(1, 0.0), Some(1));
+        assert_eq!(tree.cross_section_along_branch(0, 2.5), None);
+        assert_eq!(tree.cross_section_along_branch(2, 0.0), None);
+    }
+
+    #[test]
+    fn remove_subtrees_remaps_the_remaining_indices() {
+        let mut tree = forked_tree();
+        tree.remove_subtrees(&[1]);
+
+        assert_eq!(tree.cross_sections.len(), 3);
+        assert_eq!(tree.cross_sections[0].children_indices, vec![1]);
+        assert_eq!(tree.cross_sections[1].children_indices, vec![2]);
+        assert_eq!(tree.cross_sections[1].center, Vec3::Y);
+        assert_eq!(tree.cross_sections[2].parent_index, Some(1));
+        assert_eq!(tree.twigs.len(), 1);
+        assert_eq!(tree.twigs[0].cross_section_index, 2);
+    }
+
+    #[test]
+    fn remove_subtrees_takes_the_descendants_along() {
+        let mut tree = forked_tree();
+        tree.remove_subtrees(&[2, 7]);
+
+        assert_eq!(tree.cross_sections.len(), 1);
+        assert!(tree.cross_sections[0].children_indices.is_empty());
+        assert!(tree.twigs.is_empty());
+    }
+}
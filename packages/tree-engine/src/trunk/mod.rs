@@ -1,6 +1,6 @@
 pub mod rings;
 
-use crate::core::{TrunkGenerator, TrunkParams, TreeSubsystem};
+use crate::core::{GenerationContext, TrunkGenerator, TrunkParams, TreeSubsystem};
 use crate::structure::{TreeStructure, BranchCrossSection};
 use glam::{Vec3, Quat};
 use rand::rngs::SmallRng;
@@ -24,17 +24,17 @@ impl TreeSubsystem for TrunkSystem {
     type Output = ();
     
     fn generate(
+        &self,
         params: &Self::Params,
+        _context: &GenerationContext,
         tree: &mut TreeStructure,
         _rng: &mut SmallRng,
     ) -> Self::Output {
-        let trunk_system = TrunkSystem::new();
-        
         // Clear existing cross-sections
         tree.cross_sections.clear();
         
         // Create root cross-section with multiple rings based on buttressing
-        let trunk_rings = trunk_system.ring_generator.generate_trunk_rings(params, 0.0);
+        let trunk_rings = self.ring_generator.generate_trunk_rings(params, 0.0);
         
         let root_cross_section = BranchCrossSection {
             center: Vec3::ZERO,
@@ -42,6 +42,8 @@ impl TreeSubsystem for TrunkSystem {
             depth: 0,
             component_rings: trunk_rings,
             children_indices: Vec::new(),
+            parent_index: None,
+            branch_id: 0,
         };
         
         tree.cross_sections.push(root_cross_section);
@@ -85,4 +85,28 @@ impl RingGenerator {
             ring_type: parent_ring.ring_type.clone(),
         }
     }
-}
\ No newline at end of file
+
+    /// Shrink or grow a set of rings together, pulling their offsets in by the
+    /// same factor so the layout keeps its shape
+    pub fn scale_rings(&self, rings: &[ComponentRing], factor: f32) -> Vec<ComponentRing> {
+        rings
+            .iter()
+            .map(|ring| ComponentRing {
+                offset: ring.offset * factor,
+                radius: ring.radius * factor,
+                ring_type: ring.ring_type.clone(),
+            })
+            .collect()
+    }
+
+    /// Radius of a single ring with the same total area as `rings`
+    pub fn effective_radius(&self, rings: &[ComponentRing]) -> f32 {
+        rings.iter().map(|ring| ring.radius * ring.radius).sum::<f32>().sqrt()
+    }
+}
+
+impl Default for RingGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,9 +1,9 @@
 pub mod placement;
 
-use crate::core::{TwigGenerator, TwigParams, TreeSubsystem};
+use crate::core::{GenerationContext, TwigGenerator, TwigParams, TreeSubsystem, sub_seed};
 use crate::structure::{TreeStructure, TwigGenerationParams, RingType};
 use glam::Vec3;
-use rand::{Rng, rngs::SmallRng};
+use rand::{Rng, RngCore, SeedableRng, rngs::SmallRng};
 
 pub use placement::TwigPlacer;
 
@@ -24,7 +24,9 @@ impl TreeSubsystem for TwigSystem {
     type Output = ();
     
     fn generate(
+        &self,
         params: &Self::Params,
+        _context: &GenerationContext,
         tree: &mut TreeStructure,
         rng: &mut SmallRng,
     ) -> Self::Output {
@@ -32,8 +34,6 @@ impl TreeSubsystem for TwigSystem {
             return;
         }
 
-        let placer = TwigPlacer::new();
-        
         // Clear existing twigs
         tree.twigs.clear();
         
@@ -46,8 +46,13 @@ impl TreeSubsystem for TwigSystem {
             attachment_threshold: 0.05,
         };
         
+        // Every cross-section draws from its own RNG, so changing the density or
+        // scale only affects the twigs it actually touches
+        let stream_seed = rng.next_u64();
+        
         // Find twig attachment points in all cross-sections (excluding roots)
         for (i, cross_section) in tree.cross_sections.iter().enumerate() {
+            let rng = &mut SmallRng::seed_from_u64(sub_seed(stream_seed, i as u64));
             for ring in &cross_section.component_rings {
                 // Skip root rings
                 if matches!(ring.ring_type, RingType::Root { .. }) {
@@ -73,8 +78,9 @@ impl TreeSubsystem for TwigSystem {
                     };
                     
                     // Generate twigs at this position
-                    placer.generate_twigs_at_position(
+                    self.placer.generate_twigs_at_position(
                         &mut tree.twigs,
+                        i,
                         cross_section.center,
                         branch_direction,
                         ring.radius,
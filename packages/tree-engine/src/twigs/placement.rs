@@ -9,9 +9,11 @@ impl TwigPlacer {
         TwigPlacer
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_twigs_at_position(
         &self,
         twigs: &mut Vec<Twig>,
+        cross_section_index: usize,
         position: Vec3,
         branch_direction: Vec3,
         branch_radius: f32,
@@ -23,7 +25,7 @@ impl TwigPlacer {
         // Determine number of twigs based on density and branch size
         let base_twig_count = twig_params.density * 2.0;
         let twig_count = rng.gen_range((base_twig_count * 0.5)..=(base_twig_count * 1.5)) as u32;
-        let twig_count = twig_count.max(1).min(12);
+        let twig_count = twig_count.clamp(1, 12);
         
         for i in 0..twig_count {
             // Random angle around the branch
@@ -65,9 +67,16 @@ impl TwigPlacer {
                 orientation: Quat::from_rotation_arc(Vec3::Y, twig_direction),
                 scale,
                 twig_type,
+                cross_section_index,
             };
             
             twigs.push(twig);
         }
     }
-}
\ No newline at end of file
+}
+
+impl Default for TwigPlacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}